@@ -0,0 +1,110 @@
+use eyre::eyre;
+use regex::Regex;
+
+use crate::{changelog_ext::Document, context::Context, error::Result};
+
+/// Turns the issue, pull request and ticket references in the changelog entries into the
+/// markdown links, e.g. `#123` -> `[#123](https://github.com/foo/bar/issues/123)`.
+pub struct Autolinker {
+    patterns: Vec<(Regex, String)>,
+}
+
+impl Autolinker {
+    /// Create the autolinker with the configured patterns, followed by the forge ones.
+    pub fn new(ctx: &Context) -> Result<Self> {
+        let url = ctx.remote_url()?.trim_end_matches('/').to_string();
+        let configured = ctx
+            .autolink_config()
+            .patterns
+            .iter()
+            .map(|pattern| (pattern.pattern.clone(), pattern.url.clone()));
+        let forge = ctx
+            .forge()?
+            .references()
+            .iter()
+            .map(|(pattern, template)| (pattern.to_string(), template.replace("{url}", &url)));
+
+        let patterns = configured
+            .chain(forge)
+            .map(|(pattern, url)| {
+                Regex::new(&pattern)
+                    .map(|regex| (regex, url))
+                    .map_err(|e| eyre!("Invalid autolink pattern \"{pattern}\": {e}").into())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    /// Link the references in the entries of the releases.
+    pub fn apply(&self, changelog: &mut Document) -> Result<()> {
+        for release in changelog.releases().to_vec() {
+            let entries = changelog
+                .entries(&release)
+                .into_iter()
+                .map(|(category, entries)| {
+                    let entries = entries.iter().map(|entry| self.link(entry)).collect();
+                    (category, entries)
+                })
+                .collect();
+            changelog.set_entries(release.version(), entries)?;
+        }
+
+        Ok(())
+    }
+
+    /// Link the references in the entry, entries which already contain links are left untouched.
+    fn link(&self, entry: &str) -> String {
+        if entry.contains("](") || entry.contains("://") {
+            return entry.to_string();
+        }
+
+        // The matches of the earlier patterns take precedence over the overlapping ones
+        let mut matches: Vec<(usize, usize, String)> = vec![];
+        for (regex, url) in &self.patterns {
+            for captures in regex.captures_iter(entry) {
+                let Some(found) = captures.get(0) else {
+                    continue;
+                };
+
+                if !is_boundary(entry, found.start(), found.end())
+                    || matches
+                        .iter()
+                        .any(|(start, end, _)| found.start() < *end && *start < found.end())
+                {
+                    continue;
+                }
+
+                let mut link = String::new();
+                captures.expand(url, &mut link);
+                matches.push((
+                    found.start(),
+                    found.end(),
+                    format!("[{}]({link})", found.as_str()),
+                ));
+            }
+        }
+
+        matches.sort_by_key(|(start, _, _)| *start);
+
+        let mut result = String::new();
+        let mut last = 0;
+        for (start, end, link) in matches {
+            result.push_str(&entry[last..start]);
+            result.push_str(&link);
+            last = end;
+        }
+        result.push_str(&entry[last..]);
+
+        result
+    }
+}
+
+/// Check that the reference isn't a part of a word or a path, e.g. `abc#1` or `foo/bar#1`.
+fn is_boundary(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+
+    !before.is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '/' | '[' | '-' | '&'))
+        && !after.is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use eyre::eyre;
+use keep_a_changelog::{ChangeKind, Version};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    changelog_ext::{change_kind_name, Entries},
+    config::CategoryConfig,
+    error::Result,
+};
+
+/// Semver bump level required by the entries of a category.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BumpLevel {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl BumpLevel {
+    /// Get the bump level between the versions, `None` if the versions are equal or the current one
+    /// is lower.
+    pub fn between(previous: &Version, current: &Version) -> Self {
+        if current <= previous {
+            Self::None
+        } else if current.major != previous.major {
+            Self::Major
+        } else if current.minor != previous.minor {
+            Self::Minor
+        } else {
+            Self::Patch
+        }
+    }
+}
+
+/// Category of the changelog entries.
+#[derive(Clone, Debug)]
+pub struct Category {
+    pub name: String,
+    /// Whether the entries are hidden from the `changelog get` output.
+    pub hidden: bool,
+    pub bump: BumpLevel,
+}
+
+/// Allowed changelog categories in their display order.
+#[derive(Clone, Debug)]
+pub struct Categories(Vec<Category>);
+
+impl Categories {
+    /// Get the configured categories, or the Keep a Changelog ones if none are configured.
+    pub fn new(cfg: &[CategoryConfig]) -> Result<Self> {
+        if cfg.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let mut categories: Vec<Category> = vec![];
+        for category in cfg {
+            let name = category.name.trim();
+
+            if name.is_empty() {
+                return Err(format!("Invalid category name \"{}\"", category.name).into());
+            }
+
+            if categories.iter().any(|c| c.name.eq_ignore_ascii_case(name)) {
+                return Err(format!("Category \"{name}\" is configured more than once").into());
+            }
+
+            categories.push(Category {
+                name: name.to_string(),
+                hidden: category.hidden,
+                bump: category
+                    .bump
+                    .or_else(|| default_bump(name))
+                    .unwrap_or(BumpLevel::Patch),
+            });
+        }
+
+        Ok(Self(categories))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Category> {
+        self.0
+            .iter()
+            .find(|category| category.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Get the highest bump level required by the provided categories.
+    pub fn bump_level<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> BumpLevel {
+        names
+            .into_iter()
+            .filter_map(|name| self.find(name))
+            .map(|category| category.bump)
+            .max()
+            .unwrap_or(BumpLevel::None)
+    }
+
+    /// Prepare the changelog markdown for parsing: check that all the categories are allowed and
+    /// take out the entries of the custom categories by the release version, `None` for
+    /// `[Unreleased]`. The taken out lines are left blank, so the parsing errors point to the
+    /// right lines. The headings in the fenced code blocks are left as is.
+    pub fn extract(&self, markdown: &str) -> Result<(String, HashMap<Option<Version>, Entries>)> {
+        let release_regex = Regex::new(r"^##\s+\[?([^\]\s]+)\]?\s*-").map_err(|e| eyre!(e))?;
+        let mut lines = vec![];
+        let mut custom: HashMap<Option<Version>, Entries> = HashMap::new();
+        let mut version: Option<Version> = None;
+        let mut section: Option<(String, Vec<String>)> = None;
+        let mut fenced = false;
+
+        for (idx, line) in markdown.lines().enumerate() {
+            if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+                fenced = !fenced;
+            }
+
+            let heading = line.strip_prefix("### ").filter(|_| !fenced);
+            let boundary = line.starts_with("## ")
+                || heading.is_some()
+                || line.starts_with("---")
+                || line.starts_with("<!--")
+                || (line.starts_with('[') && line.contains("]:"));
+
+            if let (Some((_, entries)), false) = (&mut section, boundary) {
+                match line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+                    Some(entry) => entries.push(entry.trim().to_string()),
+                    None => {
+                        if let Some(entry) = entries.last_mut() {
+                            entry.push('\n');
+                            entry.push_str(line.strip_prefix("  ").unwrap_or(line).trim_end());
+                        }
+                    }
+                }
+
+                lines.push("");
+                continue;
+            }
+
+            if let Some(section) = section.take() {
+                add_section(&mut custom, &version, section);
+            }
+
+            if line.starts_with("## ") {
+                version = release_regex
+                    .captures(line)
+                    .and_then(|captures| Version::parse(&captures[1]).ok());
+            }
+
+            let Some(heading) = heading else {
+                lines.push(line);
+                continue;
+            };
+
+            let category = self.find(heading).ok_or_else(|| {
+                eyre!(
+                    "Unknown category \"{}\" at line {}, allowed categories: {}",
+                    heading.trim(),
+                    idx + 1,
+                    self.names().join(", ")
+                )
+            })?;
+
+            if heading.trim().parse::<ChangeKind>().is_ok() {
+                lines.push(line);
+                continue;
+            }
+
+            section = Some((category.name.clone(), vec![]));
+            lines.push("");
+        }
+
+        if let Some(section) = section {
+            add_section(&mut custom, &version, section);
+        }
+
+        let mut result = lines.join("\n");
+        if markdown.ends_with('\n') {
+            result.push('\n');
+        }
+
+        Ok((result, custom))
+    }
+
+    /// Render the entries of the categories in the configured order, optionally without the
+    /// hidden categories. Each category is followed by a blank line.
+    pub fn render(&self, entries: Entries, compact: bool, hide: bool) -> String {
+        // Categories are ordered by their configured position, the unknown ones go last
+        let mut groups = entries
+            .into_iter()
+            .map(|(name, entries)| {
+                let position = self
+                    .0
+                    .iter()
+                    .position(|category| category.name.eq_ignore_ascii_case(name.trim()));
+                (position, name, entries)
+            })
+            .collect::<Vec<_>>();
+        groups.retain(|(position, _, _)| !(hide && position.is_some_and(|idx| self.0[idx].hidden)));
+        groups.sort_by_key(|(position, _, _)| position.unwrap_or(usize::MAX));
+
+        let blocks = groups
+            .into_iter()
+            .map(|(position, name, entries)| {
+                let name = position.map_or(name, |idx| self.0[idx].name.clone());
+                let mut block = format!("### {name}\n");
+                if !compact {
+                    block.push('\n');
+                }
+
+                for entry in entries {
+                    for (idx, line) in entry.lines().enumerate() {
+                        let line = match idx {
+                            0 => format!("- {line}"),
+                            _ => format!("  {line}").trim_end().to_string(),
+                        };
+                        block.push_str(&line);
+                        block.push('\n');
+                    }
+                }
+                block
+            })
+            .collect::<Vec<_>>();
+
+        match blocks.is_empty() {
+            true => String::new(),
+            false => format!("{}\n", blocks.join("\n")),
+        }
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.0
+            .iter()
+            .map(|category| category.name.as_str())
+            .collect()
+    }
+}
+
+impl Default for Categories {
+    fn default() -> Self {
+        let kinds = [
+            ChangeKind::Added,
+            ChangeKind::Changed,
+            ChangeKind::Deprecated,
+            ChangeKind::Removed,
+            ChangeKind::Fixed,
+            ChangeKind::Security,
+        ];
+
+        Self(
+            kinds
+                .iter()
+                .map(|kind| Category {
+                    name: change_kind_name(kind).to_string(),
+                    hidden: false,
+                    bump: default_bump(change_kind_name(kind)).unwrap_or(BumpLevel::Patch),
+                })
+                .collect(),
+        )
+    }
+}
+
+fn default_bump(name: &str) -> Option<BumpLevel> {
+    match name.parse::<ChangeKind>().ok()? {
+        ChangeKind::Removed => Some(BumpLevel::Major),
+        ChangeKind::Added | ChangeKind::Changed | ChangeKind::Deprecated => Some(BumpLevel::Minor),
+        ChangeKind::Fixed | ChangeKind::Security => Some(BumpLevel::Patch),
+    }
+}
+
+/// Add the entries of a custom category section to its release, merging the sections of the same
+/// category and dropping the blank lines at the end of the entries.
+fn add_section(
+    custom: &mut HashMap<Option<Version>, Entries>,
+    version: &Option<Version>,
+    (name, mut entries): (String, Vec<String>),
+) {
+    for entry in entries.iter_mut() {
+        entry.truncate(entry.trim_end().len());
+    }
+
+    let release = custom.entry(version.clone()).or_default();
+    match release.iter_mut().find(|(category, _)| *category == name) {
+        Some((_, existing)) => existing.append(&mut entries),
+        None => release.push((name, entries)),
+    }
+}
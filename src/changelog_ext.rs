@@ -1,26 +1,423 @@
-use crate::{context::Context, error::Result};
+use std::{
+    collections::HashMap,
+    fs, io,
+    ops::{Deref, DerefMut},
+};
 
-use keep_a_changelog::{Changelog, ChangelogParseOptions};
+use eyre::{eyre, OptionExt};
+use keep_a_changelog::{
+    ChangeKind, Changelog, ChangelogParseOptions, Changes, Link, Release, Version,
+};
+use regex::Regex;
+use serde_json::{json, Map, Value};
 
-pub(crate) trait ChangelogExt {
-    fn from_ctx(ctx: &Context) -> Result<Changelog>;
+use crate::{
+    context::{Context, STDIO_PATH},
+    error::Result,
+    forge::LinkTemplates,
+};
+
+/// Title of the changelog if it has none, the same as the `keep_a_changelog` one.
+pub(crate) const CHANGELOG_TITLE: &str = "Changelog";
+
+/// Description of the changelog if it has none, the same as the `keep_a_changelog` one.
+const CHANGELOG_DESCRIPTION: &str =
+    "All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.0.0/)
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).";
+
+/// Entries of the categories of a release by the category name.
+pub(crate) type Entries = Vec<(String, Vec<String>)>;
+
+/// Changelog parsed with the context options, with the entries of the custom categories which
+/// `Changelog` can't hold. The entries of the releases should be changed with `set_entries`, so
+/// the custom ones follow the release.
+#[derive(Clone, Debug)]
+pub(crate) struct Document {
+    changelog: Changelog,
+    /// Entries of the custom categories by the release version, `None` for `[Unreleased]`.
+    custom: HashMap<Option<Version>, Entries>,
+}
+
+impl From<Changelog> for Document {
+    fn from(changelog: Changelog) -> Self {
+        Self {
+            changelog,
+            custom: HashMap::new(),
+        }
+    }
+}
+
+impl Deref for Document {
+    type Target = Changelog;
+
+    fn deref(&self) -> &Self::Target {
+        &self.changelog
+    }
+}
+
+impl DerefMut for Document {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.changelog
+    }
 }
 
-impl ChangelogExt for Changelog {
-    fn from_ctx(ctx: &Context) -> Result<Self> {
-        ctx.debug("Parsing changelog");
+impl Document {
+    pub fn from_ctx(ctx: &Context) -> Result<Self> {
+        Self::from_path(ctx, ctx.changelog_path())
+    }
+
+    /// Parse the changelog by the provided path with the context options.
+    pub fn from_path(ctx: &Context, path: &str) -> Result<Self> {
+        ctx.debug(format!("Parsing changelog {path}").as_str());
+        Self::from_markdown(ctx, &read(path)?)
+    }
+
+    /// Parse the changelog at the provided git revision, or the working tree one if not provided.
+    pub fn from_rev(ctx: &Context, rev: Option<&str>) -> Result<Self> {
+        let Some(rev) = rev else {
+            return Self::from_ctx(ctx);
+        };
+
+        let path = ctx.changelog_path();
+        if path == STDIO_PATH {
+            return Err("The changelog can't be read at a git revision from stdin".into());
+        }
+
+        ctx.debug(format!("Parsing changelog {path} at {rev}").as_str());
+        Self::from_markdown(ctx, &ctx.git()?.show_file(rev, path)?)
+    }
 
-        let result = Changelog::parse_from_file(
-            ctx.changelog_path(),
+    /// Parse the changelog markdown with the context options.
+    pub fn from_markdown(ctx: &Context, markdown: &str) -> Result<Self> {
+        let (markdown, custom) = ctx.categories().extract(markdown)?;
+        let changelog = Changelog::parse(
+            markdown,
             Some(ChangelogParseOptions {
                 url: Some(ctx.remote_url()?.to_owned()),
-                tag_prefix: ctx.tag_prefix(),
+                tag_prefix: ctx.tag_prefix()?,
                 head: Some(ctx.head()),
             }),
         )?;
 
         ctx.debug("Successfully parsed changelog");
 
-        Ok(result)
+        Ok(Self { changelog, custom })
     }
+
+    /// Get the entries of the release grouped by the category name, including the custom
+    /// categories.
+    pub fn entries(&self, release: &Release) -> Entries {
+        let mut entries = release.changes().entries();
+        if let Some(custom) = self.custom.get(release.version()) {
+            entries.extend(custom.iter().cloned());
+        }
+
+        entries
+    }
+
+    /// Replace the entries of the release with the provided version.
+    pub fn set_entries(&mut self, version: &Option<Version>, entries: Entries) -> Result<()> {
+        let release = self
+            .changelog
+            .releases_mut()
+            .iter_mut()
+            .find(|release| release.version() == version)
+            .ok_or_else(|| eyre!("{} release not found", release_name(version)))?;
+
+        let mut changes = Changes::default();
+        let mut custom = vec![];
+        for (category, entries) in entries {
+            match category.parse::<ChangeKind>() {
+                Ok(kind) => entries
+                    .into_iter()
+                    .for_each(|entry| changes.add(kind.clone(), entry)),
+                Err(_) => custom.push((category, entries)),
+            }
+        }
+
+        release.set_changes(changes);
+        match custom.is_empty() {
+            true => self.custom.remove(version),
+            false => self.custom.insert(version.clone(), custom),
+        };
+
+        Ok(())
+    }
+
+    /// Get the compare link of the release, built with the forge link templates.
+    pub fn release_link(&self, ctx: &Context, release: &Release) -> Result<Option<Link>> {
+        let index = self
+            .releases()
+            .iter()
+            .position(|r| r == release)
+            .ok_or_eyre("Release not found")?;
+        let previous = self.releases()[index + 1..]
+            .iter()
+            .find(|r| r.date().is_some())
+            .and_then(|r| r.version().as_ref());
+        let current = release
+            .version()
+            .as_ref()
+            .filter(|_| release.date().is_some());
+
+        let url = self.url().clone().ok_or_eyre("Missing repo URL")?;
+        let templates = ctx.link_templates()?;
+        let head = self.head();
+        let tag_name = |version: &Version| {
+            format!("{}{version}", self.tag_prefix().clone().unwrap_or_default())
+        };
+
+        let (anchor, url) = match (previous, current) {
+            (None, None) => return Ok(None),
+            (None, Some(current)) => (
+                current.to_string(),
+                LinkTemplates::render(&templates.first_release, &url, "", &tag_name(current), head),
+            ),
+            (Some(previous), None) => (
+                "Unreleased".to_string(),
+                LinkTemplates::render(&templates.unreleased, &url, &tag_name(previous), head, head),
+            ),
+            (Some(previous), Some(current)) => (
+                current.to_string(),
+                LinkTemplates::render(
+                    &templates.compare,
+                    &url,
+                    &tag_name(previous),
+                    &tag_name(current),
+                    head,
+                ),
+            ),
+        };
+
+        Ok(Some(Link { anchor, url }))
+    }
+
+    /// Get the changelog as a JSON document, each release includes its compare link.
+    pub fn to_json(&self, ctx: &Context) -> Result<Value> {
+        let mut releases = vec![];
+
+        for release in self.releases() {
+            let mut json = self.release_json(release);
+            json["link"] = json!(self.release_link(ctx, release)?.map(|link| link.url));
+            releases.push(json);
+        }
+
+        Ok(json!({
+            "title": self.title(),
+            "description": self.description(),
+            "url": self.url(),
+            "releases": releases,
+        }))
+    }
+
+    /// Get the changelog markdown in the Keep a Changelog format, with the categories in the
+    /// configured order and the compare links built with the forge link templates.
+    pub fn to_markdown(&self, ctx: &Context) -> Result<String> {
+        let compact = *self.compact();
+        let mut contents = String::new();
+
+        if let Some(lints) = self.lint() {
+            let mut lints = lints.iter().map(String::as_str).collect::<Vec<_>>();
+            lints.sort();
+            contents.push_str(&format!(
+                "<!-- markdownlint-disable {} -->\n",
+                lints.join(" ")
+            ));
+        }
+
+        if let Some(flag) = self.flag() {
+            contents.push_str(&format!("<!-- {flag} -->\n"));
+        }
+
+        let title = self.title().as_deref().unwrap_or(CHANGELOG_TITLE);
+        contents.push_str(&format!("# {title}\n"));
+        if !compact {
+            contents.push('\n');
+        }
+
+        let description = self
+            .description()
+            .as_deref()
+            .map_or(CHANGELOG_DESCRIPTION, str::trim);
+        contents.push_str(&format!("{description}\n\n"));
+
+        for release in self.releases() {
+            contents.push_str(&self.render_release(ctx, release, compact, false));
+        }
+
+        // The links legend: the links of the changelog, followed by the compare links of the
+        // releases built with the forge link templates
+        let compare_anchor = Regex::new(r"\d+\.\d+\.\d+((-rc|-x)\.\d+)?").map_err(|e| eyre!(e))?;
+        let mut links = self
+            .links()
+            .iter()
+            .filter(|link| {
+                !compare_anchor.is_match(&link.anchor) && !link.anchor.contains("Unreleased")
+            })
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        for release in self.releases() {
+            if let Some(link) = self.release_link(ctx, release)? {
+                links.push(link.to_string());
+            }
+        }
+
+        if !links.is_empty() {
+            contents = format!(
+                "{}\n\n{}\n",
+                contents.trim_end_matches('\n'),
+                links.join("\n")
+            );
+        }
+
+        if let Some(footer) = self.footer() {
+            contents.push_str(&format!("---\n{footer}\n"));
+        }
+
+        Ok(format!("{}\n", contents.trim_end_matches('\n')))
+    }
+
+    /// Write the changelog markdown to the provided path.
+    pub fn save(&self, ctx: &Context, path: &str) -> Result<()> {
+        write(path, &self.to_markdown(ctx)?)
+    }
+
+    /// Get the release as a JSON object.
+    pub fn release_json(&self, release: &Release) -> Value {
+        let mut changes = Map::new();
+        for (category, entries) in self.entries(release) {
+            changes.insert(category.to_lowercase(), json!(entries));
+        }
+
+        json!({
+            "version": release.version().as_ref().map(|v| v.to_string()),
+            "date": release.date().map(|d| d.format("%Y-%m-%d").to_string()),
+            "yanked": release.yanked(),
+            "description": release.description(),
+            "changes": changes,
+        })
+    }
+
+    /// Get the release markdown with the categories in the configured order, optionally without
+    /// the hidden categories.
+    pub fn release_markdown(&self, ctx: &Context, release: &Release, hide: bool) -> String {
+        self.render_release(ctx, release, false, hide)
+    }
+
+    /// Render the release heading, description and categories, followed by a blank line.
+    fn render_release(
+        &self,
+        ctx: &Context,
+        release: &Release,
+        compact: bool,
+        hide: bool,
+    ) -> String {
+        let mut markdown = match (release.version(), release.date()) {
+            (Some(version), Some(date)) => {
+                let yanked = if *release.yanked() { " [YANKED]" } else { "" };
+                format!("## [{version}] - {}{yanked}\n", date.format("%Y-%m-%d"))
+            }
+            (Some(version), None) => format!("## [{version}] - Unreleased\n"),
+            (None, _) => "## [Unreleased]\n".to_string(),
+        };
+
+        if !compact {
+            markdown.push('\n');
+        }
+
+        if let Some(description) = release.description() {
+            markdown.push_str(description);
+            markdown.push('\n');
+        }
+
+        let entries = self.entries(release);
+        if !entries.is_empty() {
+            markdown.push_str(&ctx.categories().render(entries, compact, hide));
+        } else if compact {
+            markdown.push('\n');
+        }
+
+        markdown
+    }
+}
+
+trait ChangesExt {
+    /// Get the change entries grouped by the category name.
+    fn entries(&self) -> Entries;
+}
+
+impl ChangesExt for Changes {
+    fn entries(&self) -> Entries {
+        // `Changes` doesn't expose its entries, so they are collected back from its markdown
+        let mut result: Entries = vec![];
+
+        for line in self.to_string().lines() {
+            if let Some(kind) = line.strip_prefix("### ") {
+                if let Ok(kind) = kind.parse::<ChangeKind>() {
+                    result.push((change_kind_name(&kind).to_string(), vec![]));
+                }
+                continue;
+            }
+
+            let Some((_, entries)) = result.last_mut() else {
+                continue;
+            };
+
+            if let Some(entry) = line.strip_prefix("- ") {
+                entries.push(entry.to_string());
+            } else if let Some(entry) = entries.last_mut() {
+                entry.push('\n');
+                entry.push_str(line.strip_prefix("  ").unwrap_or(line));
+            }
+        }
+
+        for (_, entries) in result.iter_mut() {
+            for entry in entries.iter_mut() {
+                entry.truncate(entry.trim_end().len());
+            }
+        }
+
+        result
+    }
+}
+
+/// Get the name of the release by its version, `Unreleased` if there is none.
+pub(crate) fn release_name(version: &Option<Version>) -> String {
+    match version {
+        Some(version) => version.to_string(),
+        None => "Unreleased".to_string(),
+    }
+}
+
+pub(crate) fn change_kind_name(kind: &ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Added => "Added",
+        ChangeKind::Changed => "Changed",
+        ChangeKind::Deprecated => "Deprecated",
+        ChangeKind::Removed => "Removed",
+        ChangeKind::Fixed => "Fixed",
+        ChangeKind::Security => "Security",
+    }
+}
+
+/// Read the file by the path, or stdin if the path is `-`.
+pub(crate) fn read(path: &str) -> Result<String> {
+    if path == STDIO_PATH {
+        return io::read_to_string(io::stdin())
+            .map_err(|e| eyre!("Failed to read stdin: {e}").into());
+    }
+
+    fs::read_to_string(path).map_err(|e| eyre!("Failed to read {path}: {e}").into())
+}
+
+/// Write the contents to the file by the path, or stdout if the path is `-`.
+pub(crate) fn write(path: &str, contents: &str) -> Result<()> {
+    if path == STDIO_PATH {
+        print!("{contents}");
+        return Ok(());
+    }
+
+    fs::write(path, contents).map_err(|e| eyre!("Failed to write {path}: {e}").into())
 }
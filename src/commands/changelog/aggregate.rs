@@ -0,0 +1,405 @@
+use std::path::Path;
+
+use clap::Args;
+use keep_a_changelog::{NaiveDate, Release, Version};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    changelog_ext::{self, Document, Entries, CHANGELOG_TITLE},
+    context::Context,
+    error::Result,
+    workspace,
+};
+
+use super::export::{
+    markdown_to_html, release_body, render_atom, render_html, render_json, render_rss,
+    ExportFormat, FeedEntry,
+};
+
+/// Description of the aggregated changelog.
+const AGGREGATE_DESCRIPTION: &str =
+    "All notable changes to the workspace packages, aggregated from their changelogs.";
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub(crate) struct AggregateCmd {
+    /// The path to write the aggregated changelog to, defaults to the changelog path, or stdout
+    /// if the `--format` is provided
+    #[arg(short, long)]
+    output: Option<String>,
+    /// Export the aggregated changelog in the provided format instead of the markdown
+    #[arg(short, long, value_enum)]
+    format: Option<ExportFormat>,
+    /// Check that the aggregated changelog is up to date instead of writing it
+    #[arg(long)]
+    #[serde(default)]
+    check: bool,
+}
+
+impl AggregateCmd {
+    pub(crate) fn run(self, ctx: &Context) -> Result<()> {
+        let workspace_path = ctx
+            .workspace_path()
+            .ok_or("Workspace path is required to aggregate the packages changelogs")?;
+
+        let mut releases = vec![];
+        for package in workspace::packages(&workspace_path)? {
+            let path = package.changelog_path(ctx);
+            if !Path::new(&path).exists() {
+                ctx.debug(format!("No changelog found for the {} package", package.name).as_str());
+                continue;
+            }
+
+            let package_ctx = ctx.with_package(&package);
+            let changelog = Document::from_path(&package_ctx, &path)?;
+            releases.extend(package_releases(&package_ctx, &package.name, &changelog)?);
+        }
+        sort_releases(&mut releases);
+
+        let title = CHANGELOG_TITLE.to_string();
+        let contents = match self.format {
+            Some(ExportFormat::Html) => render_html(&title, &to_markdown(ctx, &releases)),
+            Some(ExportFormat::Json) => render_json(&json!({
+                "title": title,
+                "url": ctx.remote_url()?,
+                "releases": releases.iter().map(|r| r.json.clone()).collect::<Vec<_>>(),
+            }))?,
+            Some(ExportFormat::Atom) => {
+                render_atom(&title, ctx.remote_url()?, feed_entries(&releases))
+            }
+            Some(ExportFormat::Rss) => render_rss(
+                &title,
+                ctx.remote_url()?,
+                Some(AGGREGATE_DESCRIPTION),
+                feed_entries(&releases),
+            ),
+            None => to_markdown(ctx, &releases),
+        };
+
+        let output = match (&self.output, self.format) {
+            (Some(path), _) => Some(path.clone()),
+            (None, None) => Some(ctx.changelog_path().to_owned()),
+            (None, Some(_)) => None,
+        };
+
+        match (output, self.check) {
+            (Some(path), true) => {
+                let current = changelog_ext::read(&path).unwrap_or_default();
+                if current != contents {
+                    return Err(format!(
+                        "Aggregated changelog {path} is out of date, run `rlx changelog aggregate` to update it"
+                    )
+                    .into());
+                }
+                ctx.success(&format!("Aggregated changelog {path} is up to date"));
+            }
+            (None, true) => return Err("The `--check` option requires an output path".into()),
+            (Some(path), false) => {
+                changelog_ext::write(&path, &contents)?;
+                ctx.success(&format!("Aggregated changelog written to {path}"));
+            }
+            (None, false) => print!("{contents}"),
+        }
+
+        Ok(())
+    }
+}
+
+/// Get the releases of the package changelog.
+fn package_releases(
+    ctx: &Context,
+    package: &str,
+    changelog: &Document,
+) -> Result<Vec<PackageRelease>> {
+    changelog
+        .releases()
+        .iter()
+        .map(|release| PackageRelease::new(ctx, package, changelog, release))
+        .collect()
+}
+
+/// Sort the releases of the packages: the unreleased changes go first, then the releases from the
+/// newest, by package name.
+fn sort_releases(releases: &mut [PackageRelease]) {
+    releases.sort_by(|a, b| {
+        b.date
+            .is_none()
+            .cmp(&a.date.is_none())
+            .then_with(|| b.date.cmp(&a.date))
+            .then_with(|| a.package.cmp(&b.package))
+            .then_with(|| b.version.cmp(&a.version))
+    });
+}
+
+/// Release of a workspace package, the releases of the packages are never merged, even if they
+/// have the same version.
+struct PackageRelease {
+    package: String,
+    version: Option<Version>,
+    date: Option<NaiveDate>,
+    yanked: bool,
+    description: Option<String>,
+    entries: Entries,
+    link: Option<String>,
+    body: String,
+    json: Value,
+}
+
+impl PackageRelease {
+    fn new(ctx: &Context, package: &str, changelog: &Document, release: &Release) -> Result<Self> {
+        let link = changelog.release_link(ctx, release)?.map(|link| link.url);
+
+        let mut json = changelog.release_json(release);
+        json["package"] = json!(package);
+        json["link"] = json!(link);
+
+        Ok(Self {
+            package: package.to_string(),
+            version: release.version().clone(),
+            date: *release.date(),
+            yanked: *release.yanked(),
+            description: release.description().clone(),
+            entries: changelog.entries(release),
+            link,
+            body: release_body(ctx, changelog, release),
+            json,
+        })
+    }
+
+    /// Get the package and version.
+    fn name(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{} {version}", self.package),
+            None => self.package.clone(),
+        }
+    }
+
+    /// Get the package and version with the link to the release changes if any, and the yanked
+    /// flag.
+    fn title(&self) -> String {
+        let title = match &self.link {
+            Some(link) if self.version.is_some() => format!("[{}]({link})", self.name()),
+            _ => self.name(),
+        };
+
+        match self.yanked {
+            true => format!("{title} [YANKED]"),
+            false => title,
+        }
+    }
+}
+
+/// Render the aggregated changelog in the Keep a Changelog format, so it can be parsed back. The
+/// releases of the packages are grouped by date in a release versioned by the date, e.g.
+/// `2024.5.1`, which lists the package releases, followed by the categories with the entries of
+/// each package nested under its name.
+fn to_markdown(ctx: &Context, releases: &[PackageRelease]) -> String {
+    let mut contents = format!("# {CHANGELOG_TITLE}\n\n{AGGREGATE_DESCRIPTION}\n");
+    let releases = releases
+        .iter()
+        .filter(|release| !release.entries.is_empty() || release.version.is_some())
+        .collect::<Vec<_>>();
+
+    for group in releases.chunk_by(|a, b| a.date == b.date) {
+        match group[0].date {
+            Some(date) => contents.push_str(&format!(
+                "\n## [{}] - {}\n\n",
+                date.format("%Y.%-m.%-d"),
+                date.format("%Y-%m-%d")
+            )),
+            None => contents.push_str("\n## [Unreleased]\n\n"),
+        }
+
+        let mut listed = false;
+        for release in group {
+            if release.version.is_none() && release.description.is_none() {
+                continue;
+            }
+
+            contents.push_str(&format!("- {}\n", release.title()));
+            if let Some(description) = &release.description {
+                for line in description.trim().lines() {
+                    contents.push_str(format!("  {line}").trim_end());
+                    contents.push('\n');
+                }
+            }
+            listed = true;
+        }
+
+        // The entries of the packages are nested under the package name in each category
+        let mut entries: Entries = vec![];
+        for release in group {
+            for (category, package_entries) in &release.entries {
+                let mut entry = release.name();
+                for line in package_entries.iter().flat_map(|entry| nested(entry)) {
+                    entry.push('\n');
+                    entry.push_str(&line);
+                }
+
+                match entries.iter_mut().find(|(name, _)| name == category) {
+                    Some((_, existing)) => existing.push(entry),
+                    None => entries.push((category.clone(), vec![entry])),
+                }
+            }
+        }
+
+        if !entries.is_empty() {
+            if listed {
+                contents.push('\n');
+            }
+            contents.push_str(ctx.categories().render(entries, false, false).trim_end());
+            contents.push('\n');
+        }
+    }
+
+    contents
+}
+
+/// Get the lines of the entry as a nested list item.
+fn nested(entry: &str) -> Vec<String> {
+    entry
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| match idx {
+            0 => format!("- {line}"),
+            _ => format!("  {line}").trim_end().to_string(),
+        })
+        .collect()
+}
+
+/// Get the feed entries of the packages releases, newest first.
+fn feed_entries(releases: &[PackageRelease]) -> Vec<FeedEntry> {
+    releases
+        .iter()
+        .filter_map(|release| {
+            let (Some(version), Some(date)) = (&release.version, release.date) else {
+                return None;
+            };
+
+            let title = match release.yanked {
+                true => format!("{} {version} [YANKED]", release.package),
+                false => format!("{} {version}", release.package),
+            };
+
+            Some(FeedEntry {
+                title,
+                link: release.link.clone().unwrap_or_default(),
+                date,
+                content: markdown_to_html(&release.body),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use keep_a_changelog::{NaiveDate, Version};
+
+    use super::{package_releases, sort_releases, to_markdown};
+    use crate::{changelog_ext::Document, config::Config, context::Context};
+
+    const PACKAGE_A: &str = "# Changelog
+
+## [Unreleased]
+
+### Added
+
+- a next
+
+## [1.0.0] - 2024-03-01
+
+First stable release.
+
+### Added
+
+- a one
+  with details
+
+### Fixed
+
+- a fix
+
+## [0.1.0] - 2024-01-01
+
+### Added
+
+- a init
+";
+
+    const PACKAGE_B: &str = "# Changelog
+
+## [1.0.0] - 2024-02-10 [YANKED]
+
+### Changed
+
+- b one
+
+## [0.1.0] - 2024-01-01
+
+### Added
+
+- b init
+";
+
+    #[test]
+    fn aggregated_changelog_parses_back() {
+        let ctx = Context::from_config(Config {
+            remote_url: Some("https://github.com/s/mono".to_string()),
+            ..Config::default()
+        });
+
+        let mut releases = vec![];
+        for (package, markdown) in [("b", PACKAGE_B), ("a", PACKAGE_A)] {
+            let changelog = Document::from_markdown(&ctx, markdown).unwrap();
+            releases.extend(package_releases(&ctx, package, &changelog).unwrap());
+        }
+        sort_releases(&mut releases);
+
+        let aggregated = Document::from_markdown(&ctx, &to_markdown(&ctx, &releases)).unwrap();
+        let releases = aggregated
+            .releases()
+            .iter()
+            .map(|release| (release.version().clone(), *release.date()))
+            .collect::<Vec<_>>();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+        assert_eq!(
+            releases,
+            vec![
+                (None, None),
+                (Some(Version::new(2024, 3, 1)), date(2024, 3, 1)),
+                (Some(Version::new(2024, 2, 10)), date(2024, 2, 10)),
+                (Some(Version::new(2024, 1, 1)), date(2024, 1, 1)),
+            ]
+        );
+
+        let entries = |idx: usize| aggregated.entries(&aggregated.releases()[idx]);
+        assert_eq!(
+            entries(0),
+            vec![("Added".to_string(), vec!["a\n- a next".to_string()])]
+        );
+        assert_eq!(
+            entries(1),
+            vec![
+                (
+                    "Added".to_string(),
+                    vec!["a 1.0.0\n- a one\n  with details".to_string()]
+                ),
+                ("Fixed".to_string(), vec!["a 1.0.0\n- a fix".to_string()]),
+            ]
+        );
+        assert_eq!(
+            entries(3),
+            vec![(
+                "Added".to_string(),
+                vec![
+                    "a 0.1.0\n- a init".to_string(),
+                    "b 0.1.0\n- b init".to_string()
+                ]
+            )]
+        );
+
+        let description = aggregated.releases()[2].description().clone().unwrap();
+        assert!(description.starts_with("- [b 1.0.0]("));
+        assert!(description.ends_with(") [YANKED]"));
+    }
+}
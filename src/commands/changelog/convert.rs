@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use clap::{Args, ValueEnum};
+use eyre::eyre;
+use keep_a_changelog::{
+    changelog::ChangelogBuilder, ChangeKind, Changelog, ChangelogParseOptions, Changes, NaiveDate,
+    Release, Version,
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    changelog_ext::{self, Document},
+    context::Context,
+    error::{Error, Result},
+};
+
+/// Default mapping of the section names used by other changelog formats to the Keep a Changelog
+/// categories.
+const DEFAULT_SECTIONS: &[(&str, ChangeKind)] = &[
+    ("added", ChangeKind::Added),
+    ("changed", ChangeKind::Changed),
+    ("deprecated", ChangeKind::Deprecated),
+    ("removed", ChangeKind::Removed),
+    ("fixed", ChangeKind::Fixed),
+    ("security", ChangeKind::Security),
+    ("features", ChangeKind::Added),
+    ("feature", ChangeKind::Added),
+    ("bug fixes", ChangeKind::Fixed),
+    ("bug fix", ChangeKind::Fixed),
+    ("fixes", ChangeKind::Fixed),
+    ("breaking changes", ChangeKind::Changed),
+    ("performance improvements", ChangeKind::Changed),
+    ("code refactoring", ChangeKind::Changed),
+    ("reverts", ChangeKind::Changed),
+    ("documentation", ChangeKind::Changed),
+    ("styles", ChangeKind::Changed),
+    ("tests", ChangeKind::Changed),
+    ("build system", ChangeKind::Changed),
+    ("continuous integration", ChangeKind::Changed),
+    ("miscellaneous chores", ChangeKind::Changed),
+    ("chores", ChangeKind::Changed),
+    ("dependencies", ChangeKind::Changed),
+    ("deprecations", ChangeKind::Deprecated),
+];
+
+#[derive(Clone, Copy, Debug, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum SourceFormat {
+    /// conventional-changelog/standard-version output, e.g. `## [1.2.0](url) (2024-01-01)`
+    Conventional,
+    /// Free-form release headings, e.g. `## v1.0.0 - 2024-01-01`
+    FreeForm,
+}
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub(crate) struct ConvertCmd {
+    /// The format of the source changelog
+    #[arg(long, value_enum)]
+    from: SourceFormat,
+    /// The path to the source changelog, if not provided, the changelog is converted in place
+    #[arg(short, long)]
+    input: Option<String>,
+    /// Map a section name of the source changelog to a Keep a Changelog category
+    /// (e.g. `--map "Performance Improvements=Changed"`), can be used multiple times.
+    ///
+    /// Can be set via `convert.sections` config option in the `.rlx.yml` file.
+    #[arg(short, long, value_name = "SECTION=CATEGORY")]
+    #[serde(default)]
+    map: Vec<String>,
+}
+
+/// Release collected from the source changelog.
+struct SourceRelease {
+    version: Option<Version>,
+    date: Option<NaiveDate>,
+    description: Vec<String>,
+    changes: Changes,
+}
+
+impl ConvertCmd {
+    pub(crate) fn run(self, ctx: &Context) -> Result<()> {
+        let input = self
+            .input
+            .clone()
+            .unwrap_or_else(|| ctx.changelog_path().to_owned());
+
+        ctx.debug(format!("Converting changelog from {input}").as_str());
+
+        let markdown = changelog_ext::read(&input)?;
+        let sections = self.sections(ctx)?;
+        let releases = self.parse_releases(ctx, &markdown, &sections)?;
+
+        ctx.debug(format!("Found {} release(s)", releases.len()).as_str());
+
+        let mut result = vec![];
+        for release in releases {
+            let mut builder = Release::builder();
+
+            match (release.version, release.date) {
+                (Some(version), Some(date)) => {
+                    builder.version(version).date(date);
+                }
+                (Some(version), None) => {
+                    return Err(format!("Release {version} has no date").into());
+                }
+                _ => {}
+            }
+
+            if !release.description.is_empty() {
+                builder.description(release.description.join("\n"));
+            }
+
+            result.push(
+                builder
+                    .changes(release.changes)
+                    .build()
+                    .map_err(Error::from)?,
+            );
+        }
+
+        if !result
+            .iter()
+            .any(|r| r.version().is_none() && r.date().is_none())
+        {
+            result.push(Release::builder().build().map_err(Error::from)?);
+        }
+
+        let changelog = ChangelogBuilder::default()
+            .url(ctx.remote_url()?.to_owned())
+            .tag_prefix(ctx.tag_prefix()?)
+            .head(ctx.head())
+            .releases(result)
+            .build()
+            .map_err(Error::from)?;
+
+        Changelog::parse(
+            changelog.to_string(),
+            Some(ChangelogParseOptions {
+                url: Some(ctx.remote_url()?.to_owned()),
+                tag_prefix: ctx.tag_prefix()?,
+                head: Some(ctx.head()),
+            }),
+        )
+        .map_err(|e| eyre!("Converted changelog is invalid: {e}"))?;
+
+        Document::from(changelog).save(ctx, ctx.changelog_path())?;
+        ctx.success(&format!(
+            "Changelog converted successfully to {}",
+            ctx.changelog_path()
+        ));
+
+        Ok(())
+    }
+
+    /// Get the mapping of the source section names to the Keep a Changelog categories, the
+    /// `--map` options take precedence over the config, and the config over the defaults.
+    fn sections(&self, ctx: &Context) -> Result<HashMap<String, ChangeKind>> {
+        let mut sections = DEFAULT_SECTIONS
+            .iter()
+            .map(|(name, kind)| (name.to_string(), kind.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let configured = ctx
+            .convert_config()
+            .sections
+            .iter()
+            .map(|(name, kind)| (name.clone(), kind.clone()));
+        let overrides = self
+            .map
+            .iter()
+            .map(|mapping| {
+                mapping
+                    .split_once('=')
+                    .map(|(name, kind)| (name.to_string(), kind.to_string()))
+                    .ok_or_else(|| {
+                        Error::from(format!(
+                            "Invalid section mapping \"{mapping}\", expected `SECTION=CATEGORY`"
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (name, kind) in configured.chain(overrides) {
+            let kind = kind
+                .trim()
+                .parse::<ChangeKind>()
+                .map_err(|e| eyre!("Invalid category for the \"{name}\" section: {e}"))?;
+            sections.insert(normalize_section(&name), kind);
+        }
+
+        Ok(sections)
+    }
+
+    fn parse_releases(
+        &self,
+        ctx: &Context,
+        markdown: &str,
+        sections: &HashMap<String, ChangeKind>,
+    ) -> Result<Vec<SourceRelease>> {
+        let heading_regex = Regex::new(r"^(#{1,4})\s+(.*?)\s*$").map_err(|e| eyre!(e))?;
+        let item_regex = Regex::new(r"^[-*+]\s+(.*)$").map_err(|e| eyre!(e))?;
+        let release_regex = match self.from {
+            SourceFormat::Conventional => Regex::new(
+                r"^\[?v?(?P<version>\d+\.\d+\.\d+[0-9A-Za-z.+-]*)\]?(?:\([^)]*\))?\s*\((?P<date>\d{4}-\d{1,2}-\d{1,2})\)",
+            ),
+            SourceFormat::FreeForm => Regex::new(
+                r"^\[?v?(?P<version>\d+\.\d+\.\d+[0-9A-Za-z.+-]*)\]?(?:\s*[-–—:]?\s*\(?(?P<date>\d{4}-\d{1,2}-\d{1,2})\)?)?",
+            ),
+        }
+        .map_err(|e| eyre!(e))?;
+        let unreleased_regex = Regex::new(r"(?i)^\[?unreleased\]?").map_err(|e| eyre!(e))?;
+        let link_definition_regex = Regex::new(r"^\[[^\]]+\]:\s").map_err(|e| eyre!(e))?;
+
+        let mut releases: Vec<SourceRelease> = vec![];
+        let mut kind: Option<ChangeKind> = None;
+        let mut entry: Option<(ChangeKind, String)> = None;
+
+        let lines = markdown.lines().collect::<Vec<_>>();
+        for (idx, line) in lines.iter().enumerate() {
+            let line_number = idx + 1;
+            let trimmed = line.trim();
+
+            // A blank line closes the entry, unless the next line is indented as its continuation
+            if trimmed.is_empty() {
+                let continued = lines[idx + 1..]
+                    .iter()
+                    .find(|line| !line.trim().is_empty())
+                    .is_some_and(|line| line.starts_with([' ', '\t']));
+                if !continued {
+                    flush_entry(&mut releases, &mut entry);
+                }
+                continue;
+            }
+
+            if (trimmed.starts_with('<') && trimmed.ends_with('>'))
+                || trimmed.starts_with("---")
+                || link_definition_regex.is_match(trimmed)
+            {
+                flush_entry(&mut releases, &mut entry);
+                continue;
+            }
+
+            if let Some(heading) = heading_regex.captures(line) {
+                flush_entry(&mut releases, &mut entry);
+                let text = heading[2].to_string();
+
+                if let Some(captures) = release_regex.captures(&text) {
+                    let version = Version::parse(&captures["version"])
+                        .map_err(|e| eyre!("Failed to parse version at line {line_number}: {e}"))?;
+                    let date = captures
+                        .name("date")
+                        .map(|date| NaiveDate::parse_from_str(date.as_str(), "%Y-%m-%d"))
+                        .transpose()
+                        .map_err(|e| eyre!("Failed to parse date at line {line_number}: {e}"))?;
+
+                    ctx.debug(format!("Found release {version} at line {line_number}").as_str());
+                    releases.push(SourceRelease::new(Some(version), date));
+                    kind = None;
+                    continue;
+                }
+
+                if unreleased_regex.is_match(&text) {
+                    releases.push(SourceRelease::new(None, None));
+                    kind = None;
+                    continue;
+                }
+
+                if releases.is_empty() {
+                    continue;
+                }
+
+                let section = normalize_section(&text);
+                kind = Some(sections.get(&section).cloned().ok_or_else(|| {
+                    Error::from(format!(
+                        "Unknown section \"{text}\" at line {line_number}, map it to a Keep a Changelog category with `--map \"{text}=<CATEGORY>\"`"
+                    ))
+                })?);
+                continue;
+            }
+
+            let Some(release) = releases.last_mut() else {
+                continue;
+            };
+
+            if let Some(item) = item_regex.captures(trimmed) {
+                flush_entry(&mut releases, &mut entry);
+                let kind = kind.clone().unwrap_or(ChangeKind::Changed);
+                entry = Some((kind, item[1].to_string()));
+                continue;
+            }
+
+            if let Some((_, text)) = entry.as_mut() {
+                text.push('\n');
+                text.push_str(trimmed);
+                continue;
+            }
+
+            match kind {
+                None => release.description.push(trimmed.to_string()),
+                Some(_) => ctx.debug(format!("Skipping text at line {line_number}").as_str()),
+            }
+        }
+
+        flush_entry(&mut releases, &mut entry);
+
+        Ok(releases)
+    }
+}
+
+impl SourceRelease {
+    fn new(version: Option<Version>, date: Option<NaiveDate>) -> Self {
+        Self {
+            version,
+            date,
+            description: vec![],
+            changes: Changes::default(),
+        }
+    }
+}
+
+fn flush_entry(releases: &mut [SourceRelease], entry: &mut Option<(ChangeKind, String)>) {
+    if let (Some(release), Some((kind, text))) = (releases.last_mut(), entry.take()) {
+        release.changes.add(kind, text);
+    }
+}
+
+/// Normalize the section name for the lookup, e.g. `⚠ BREAKING CHANGES` -> `breaking changes`.
+fn normalize_section(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
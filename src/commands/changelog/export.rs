@@ -0,0 +1,275 @@
+use std::fs;
+
+use clap::{Args, ValueEnum};
+use eyre::eyre;
+use keep_a_changelog::{NaiveDate, Release};
+use pulldown_cmark::{html, Parser};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{changelog_ext::Document, commands::escape_xml, context::Context, error::Result};
+
+#[derive(Clone, Copy, Debug, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ExportFormat {
+    /// Standalone HTML page
+    Html,
+    /// JSON document with all the releases
+    Json,
+    /// Atom feed with an entry per release
+    Atom,
+    /// RSS feed with an item per release
+    Rss,
+}
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub(crate) struct ExportCmd {
+    /// The export format
+    #[arg(short, long, value_enum)]
+    format: ExportFormat,
+    /// The path to write the export to, if not provided, the export is written to stdout
+    #[arg(short, long)]
+    output: Option<String>,
+    /// The title of the HTML page or feed, defaults to the changelog title
+    #[arg(long)]
+    title: Option<String>,
+    /// Read the changelog at the git revision (e.g. a tag or a commit) instead of the working tree
+    #[arg(long)]
+    #[serde(default)]
+    rev: Option<String>,
+}
+
+impl ExportCmd {
+    pub(crate) fn run(self, ctx: &Context) -> Result<()> {
+        let changelog = Document::from_rev(ctx, self.rev.as_deref())?;
+        let output = export(ctx, &changelog, self.format, self.title.clone())?;
+
+        match &self.output {
+            Some(path) => {
+                fs::write(path, output).map_err(|e| eyre!("Failed to write {path}: {e}"))?;
+                ctx.success(&format!("Changelog exported to {path}"));
+            }
+            None => print!("{output}"),
+        }
+
+        Ok(())
+    }
+}
+
+/// Export the changelog in the requested format.
+pub(super) fn export(
+    ctx: &Context,
+    changelog: &Document,
+    format: ExportFormat,
+    title: Option<String>,
+) -> Result<String> {
+    ctx.debug(format!("Exporting changelog as {format:?}").as_str());
+
+    let title = title
+        .or_else(|| changelog.title().clone())
+        .unwrap_or_else(|| "Changelog".to_string());
+
+    let url = changelog.url().clone().unwrap_or_default();
+    match format {
+        ExportFormat::Html => Ok(render_html(&title, &changelog.to_markdown(ctx)?)),
+        ExportFormat::Json => render_json(&changelog.to_json(ctx)?),
+        ExportFormat::Atom => Ok(render_atom(&title, &url, feed_entries(ctx, changelog)?)),
+        ExportFormat::Rss => Ok(render_rss(
+            &title,
+            &url,
+            changelog.description().as_deref(),
+            feed_entries(ctx, changelog)?,
+        )),
+    }
+}
+
+pub(super) fn render_json(value: &Value) -> Result<String> {
+    Ok(format!(
+        "{}\n",
+        serde_json::to_string_pretty(value)
+            .map_err(|e| eyre!("Failed to serialize changelog: {e}"))?
+    ))
+}
+
+pub(super) fn render_html(title: &str, markdown: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{}</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; line-height: 1.5; max-width: 48rem; margin: 0 auto; padding: 2rem 1rem; color: #1f2328; }}
+h2 {{ border-bottom: 1px solid #d1d9e0; padding-bottom: 0.3em; }}
+a {{ color: #0969da; }}
+code {{ background: #eff1f3; border-radius: 4px; padding: 0.1em 0.3em; }}
+</style>
+</head>
+<body>
+<main>
+{}</main>
+</body>
+</html>
+"#,
+        escape_xml(title),
+        markdown_to_html(markdown)
+    )
+}
+
+pub(super) fn render_atom(title: &str, url: &str, entries: Vec<FeedEntry>) -> String {
+    let updated = entries.first().map(|entry| entry.date).unwrap_or_default();
+
+    let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str(&format!("  <title>{}</title>\n", escape_xml(title)));
+    feed.push_str(&format!("  <id>{}</id>\n", escape_xml(url)));
+    feed.push_str(&format!("  <link href=\"{}\"/>\n", escape_xml(url)));
+    feed.push_str(&format!("  <updated>{}</updated>\n", atom_date(updated)));
+    feed.push_str(&format!(
+        "  <author><name>{}</name></author>\n",
+        escape_xml(repository_name(url))
+    ));
+
+    for entry in entries {
+        feed.push_str("  <entry>\n");
+        feed.push_str(&format!(
+            "    <title>{}</title>\n",
+            escape_xml(&entry.title)
+        ));
+        feed.push_str(&format!("    <id>{}</id>\n", escape_xml(&entry.link)));
+        feed.push_str(&format!(
+            "    <link href=\"{}\"/>\n",
+            escape_xml(&entry.link)
+        ));
+        feed.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            atom_date(entry.date)
+        ));
+        feed.push_str(&format!(
+            "    <content type=\"html\">{}</content>\n",
+            escape_xml(&entry.content)
+        ));
+        feed.push_str("  </entry>\n");
+    }
+
+    feed.push_str("</feed>\n");
+    feed
+}
+
+pub(super) fn render_rss(
+    title: &str,
+    url: &str,
+    description: Option<&str>,
+    entries: Vec<FeedEntry>,
+) -> String {
+    let description = description
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Release notes of {}", repository_name(url)));
+
+    let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<rss version=\"2.0\">\n<channel>\n");
+    feed.push_str(&format!("  <title>{}</title>\n", escape_xml(title)));
+    feed.push_str(&format!("  <link>{}</link>\n", escape_xml(url)));
+    feed.push_str(&format!(
+        "  <description>{}</description>\n",
+        escape_xml(&description)
+    ));
+
+    for entry in entries {
+        feed.push_str("  <item>\n");
+        feed.push_str(&format!(
+            "    <title>{}</title>\n",
+            escape_xml(&entry.title)
+        ));
+        feed.push_str(&format!("    <link>{}</link>\n", escape_xml(&entry.link)));
+        feed.push_str(&format!(
+            "    <guid isPermaLink=\"true\">{}</guid>\n",
+            escape_xml(&entry.link)
+        ));
+        feed.push_str(&format!(
+            "    <pubDate>{}</pubDate>\n",
+            entry
+                .date
+                .and_hms_opt(0, 0, 0)
+                .unwrap_or_default()
+                .and_utc()
+                .to_rfc2822()
+        ));
+        feed.push_str(&format!(
+            "    <description>{}</description>\n",
+            escape_xml(&entry.content)
+        ));
+        feed.push_str("  </item>\n");
+    }
+
+    feed.push_str("</channel>\n</rss>\n");
+    feed
+}
+
+/// Release rendered for a feed.
+pub(super) struct FeedEntry {
+    pub title: String,
+    pub link: String,
+    pub date: NaiveDate,
+    pub content: String,
+}
+
+/// Get the feed entries of the released versions, newest first.
+fn feed_entries(ctx: &Context, changelog: &Document) -> Result<Vec<FeedEntry>> {
+    let mut entries = vec![];
+
+    for release in changelog.releases() {
+        let (Some(version), Some(date)) = (release.version(), release.date()) else {
+            continue;
+        };
+
+        let link = changelog
+            .release_link(ctx, release)?
+            .map(|link| link.url)
+            .unwrap_or_default();
+        let title = match release.yanked() {
+            true => format!("{version} [YANKED]"),
+            false => version.to_string(),
+        };
+
+        entries.push(FeedEntry {
+            title,
+            link,
+            date: *date,
+            content: markdown_to_html(&release_body(ctx, changelog, release)),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Get the release markdown without the heading.
+pub(super) fn release_body(ctx: &Context, changelog: &Document, release: &Release) -> String {
+    let mut body = String::new();
+
+    if let Some(description) = release.description() {
+        body.push_str(description);
+        body.push_str("\n\n");
+    }
+
+    body.push_str(
+        &ctx.categories()
+            .render(changelog.entries(release), false, false),
+    );
+    body
+}
+
+pub(super) fn markdown_to_html(markdown: &str) -> String {
+    let mut output = String::new();
+    html::push_html(&mut output, Parser::new(markdown));
+    output
+}
+
+fn atom_date(date: NaiveDate) -> String {
+    format!("{}T00:00:00Z", date.format("%Y-%m-%d"))
+}
+
+fn repository_name(url: &str) -> &str {
+    url.trim_end_matches('/').rsplit('/').next().unwrap_or(url)
+}
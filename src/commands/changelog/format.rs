@@ -1,16 +1,27 @@
 use clap::Args;
-use keep_a_changelog::Changelog;
 use serde::{Deserialize, Serialize};
 
-use crate::{changelog_ext::ChangelogExt, context::Context, error::Result};
+use crate::{autolink::Autolinker, changelog_ext::Document, context::Context, error::Result};
 
 #[derive(Clone, Args, Debug, Serialize, Deserialize)]
-pub(crate) struct FormatCmd;
+pub(crate) struct FormatCmd {
+    /// Turn the issue and pull request references (e.g. `#123`) in the entries into links.
+    ///
+    /// Can be set via `autolink.enabled` config option in the `.rlx.yml` file.
+    #[arg(long)]
+    #[serde(default)]
+    autolink: bool,
+}
 
 impl FormatCmd {
     pub(crate) fn run(self, ctx: &Context) -> Result<()> {
-        let changelog = Changelog::from_ctx(ctx)?;
-        changelog.save_to_file(ctx.changelog_path())?;
+        let mut changelog = Document::from_ctx(ctx)?;
+
+        if self.autolink || ctx.autolink_config().enabled.unwrap_or_default() {
+            Autolinker::new(ctx)?.apply(&mut changelog)?;
+        }
+
+        changelog.save(ctx, ctx.changelog_path())?;
         ctx.success("Changelog formatted successfully");
         Ok(())
     }
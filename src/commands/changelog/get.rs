@@ -1,29 +1,55 @@
-use clap::Args;
-use keep_a_changelog::Changelog;
+use clap::{Args, ValueEnum};
+use keep_a_changelog::Release;
 use serde::{Deserialize, Serialize};
 
-use crate::{changelog_ext::ChangelogExt, context::Context, error::Result};
+use crate::{autolink::Autolinker, changelog_ext::Document, context::Context, error::Result};
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum GetFormat {
+    /// Markdown of the release
+    #[default]
+    Text,
+    /// JSON object with the release version, date, yanked flag and changes
+    Json,
+}
 
 #[derive(Clone, Args, Debug, Serialize, Deserialize)]
 pub(crate) struct GetCmd {
-    /// Get changes in a specific release. Use "latest" for the latest release
+    /// Get changes in a specific release. Use "latest" for the latest not yanked release
     version: String,
+    /// Output format of the release
+    #[arg(short, long, value_enum, default_value_t)]
+    #[serde(default)]
+    format: GetFormat,
+    /// Turn the issue and pull request references (e.g. `#123`) in the entries into links.
+    ///
+    /// Can be set via `autolink.enabled` config option in the `.rlx.yml` file.
+    #[arg(long)]
+    #[serde(default)]
+    autolink: bool,
+    /// Read the changelog at the git revision (e.g. a tag or a commit) instead of the working tree
+    #[arg(long)]
+    #[serde(default)]
+    rev: Option<String>,
 }
 
 impl GetCmd {
     pub(crate) fn run(self, ctx: &Context) -> Result<()> {
         let version = self.version.replace('v', "");
-        let changelog = Changelog::from_ctx(ctx)?;
+        let mut changelog = Document::from_rev(ctx, self.rev.as_deref())?;
+
+        if self.autolink || ctx.autolink_config().enabled.unwrap_or_default() {
+            Autolinker::new(ctx)?.apply(&mut changelog)?;
+        }
 
         if version == "latest" {
-            let release = changelog
-                .releases()
-                .iter()
-                .find(|release| release.date().is_some() && release.version().is_some());
+            let release = changelog.releases().iter().find(|release| {
+                release.date().is_some() && release.version().is_some() && !release.yanked()
+            });
 
             if let Some(release) = release {
-                println!("{}", release);
-                return Ok(());
+                return self.print(ctx, &changelog, release);
             }
 
             return Err("Latest release not found".into());
@@ -32,10 +58,33 @@ impl GetCmd {
         let release = changelog.find_release(self.version.clone())?;
 
         if let Some(release) = release {
-            println!("{}", release);
-            return Ok(());
+            return self.print(ctx, &changelog, release);
         }
 
         Err(format!("{} release not found", self.version).into())
     }
+
+    fn print(&self, ctx: &Context, changelog: &Document, release: &Release) -> Result<()> {
+        match self.format {
+            GetFormat::Text => println!("{}", changelog.release_markdown(ctx, release, true)),
+            GetFormat::Json => {
+                let mut json = changelog.release_json(release);
+                if let Some(changes) = json["changes"].as_object_mut() {
+                    changes.retain(|category, _| {
+                        !ctx.categories()
+                            .find(category)
+                            .is_some_and(|category| category.hidden)
+                    });
+                }
+
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&json)
+                        .map_err(|e| format!("Failed to serialize release: {e}"))?
+                )
+            }
+        }
+
+        Ok(())
+    }
 }
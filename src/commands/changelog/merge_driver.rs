@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use clap::Args;
+use eyre::eyre;
+use keep_a_changelog::{Release, Version};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    changelog_ext::{release_name, Document, Entries},
+    context::Context,
+    error::{Error, Result},
+};
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub(crate) struct MergeDriverCmd {
+    /// The common ancestor version of the changelog (`%O`)
+    base: String,
+    /// The current branch version of the changelog (`%A`), the merge result is written to it
+    current: String,
+    /// The other branch version of the changelog (`%B`)
+    other: String,
+}
+
+impl MergeDriverCmd {
+    pub(crate) fn run(self, ctx: &Context) -> Result<()> {
+        match self.merge(ctx) {
+            Ok(changelog) => {
+                changelog.save(ctx, &self.current)?;
+                ctx.success("Changelog merged successfully");
+                return Ok(());
+            }
+            Err(e) => ctx.info(&format!(
+                "Failed to merge the changelog structurally, falling back to the line merge: {}",
+                e.message()
+            )),
+        }
+
+        if !ctx
+            .git()?
+            .merge_file(&self.current, &self.base, &self.other)?
+        {
+            return Err("Changelog has merge conflicts, resolve them manually".into());
+        }
+
+        ctx.success("Changelog merged successfully");
+        Ok(())
+    }
+
+    /// Merge the releases of the changelogs, the entries of each category added on either side
+    /// are kept, and the entries removed on either side are dropped. An entry changed differently
+    /// on both sides is a conflict.
+    fn merge(&self, ctx: &Context) -> Result<Document> {
+        let base = Document::from_path(ctx, &self.base)?;
+        let current = Document::from_path(ctx, &self.current)?;
+        let other = Document::from_path(ctx, &self.other)?;
+
+        let find = |changelog: &Document, version: &Option<Version>| {
+            changelog
+                .releases()
+                .iter()
+                .find(|release| release.version() == version)
+                .map(|release| (release.clone(), changelog.entries(release)))
+        };
+
+        let mut versions = current
+            .releases()
+            .iter()
+            .map(|release| release.version().clone())
+            .collect::<Vec<_>>();
+        for release in other.releases() {
+            if !versions.contains(release.version()) {
+                versions.push(release.version().clone());
+            }
+        }
+
+        let mut releases = vec![];
+        for version in versions {
+            let name = release_name(&version);
+            let base = find(&base, &version);
+
+            match (base, find(&current, &version), find(&other, &version)) {
+                (base, Some(current), Some(other)) => {
+                    releases.push(merge_release(&name, base, current, other)?);
+                }
+                (None, Some(release), None) | (None, None, Some(release)) => releases.push(release),
+                (Some(base), Some(release), None) | (Some(base), None, Some(release)) => {
+                    if release != base {
+                        return Err(format!(
+                            "{name} release is removed on one side and changed on the other"
+                        )
+                        .into());
+                    }
+                }
+                (_, None, None) => {}
+            }
+        }
+
+        releases.sort_by(|(a, _), (b, _)| match (a.version(), b.version()) {
+            (None, Some(_)) => std::cmp::Ordering::Less,
+            (Some(_), None) => std::cmp::Ordering::Greater,
+            _ => b.cmp(a),
+        });
+
+        let mut changelog = current;
+        *changelog.releases_mut() = releases
+            .iter()
+            .map(|(release, _)| release.clone())
+            .collect();
+        for (release, entries) in releases {
+            changelog.set_entries(release.version(), entries)?;
+        }
+
+        Ok(changelog)
+    }
+}
+
+fn merge_release(
+    name: &str,
+    base: Option<(Release, Entries)>,
+    (current, current_entries): (Release, Entries),
+    (other, other_entries): (Release, Entries),
+) -> Result<(Release, Entries)> {
+    let (base, base_entries) = base.unzip();
+    let date = merge_value(
+        &format!("Date of the {name} release"),
+        base.as_ref().map(|release| *release.date()),
+        *current.date(),
+        *other.date(),
+    )?;
+    let yanked = merge_value(
+        &format!("Yanked mark of the {name} release"),
+        base.as_ref().map(|release| *release.yanked()),
+        *current.yanked(),
+        *other.yanked(),
+    )?;
+    let description = merge_value(
+        &format!("Description of the {name} release"),
+        base.as_ref().map(|release| release.description().clone()),
+        current.description().clone(),
+        other.description().clone(),
+    )?;
+
+    let base_entries = base_entries
+        .unwrap_or_default()
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let other_entries = other_entries.into_iter().collect::<HashMap<_, _>>();
+
+    let mut categories = current_entries
+        .iter()
+        .map(|(category, _)| category.clone())
+        .collect::<Vec<_>>();
+    for category in other_entries.keys() {
+        if !categories.contains(category) {
+            categories.push(category.clone());
+        }
+    }
+
+    let mut entries = vec![];
+    for category in categories {
+        let empty = vec![];
+        let base = base_entries.get(&category).unwrap_or(&empty);
+        let other = other_entries.get(&category).unwrap_or(&empty);
+        let current = current_entries
+            .iter()
+            .find(|(name, _)| *name == category)
+            .map_or(&empty, |(_, entries)| entries);
+
+        let other_edits = edits(base, other);
+        for (entry, edited) in edits(base, current) {
+            if other_edits.get(entry).is_some_and(|other| *other != edited) {
+                return Err(eyre!(
+                    "{category} entry \"{entry}\" of the {name} release is changed on both sides"
+                )
+                .into());
+            }
+        }
+
+        let merged = merge_entries(base, current, other);
+        if !merged.is_empty() {
+            entries.push((category, merged));
+        }
+    }
+
+    let mut builder = Release::builder();
+    builder.yanked(yanked).description(description);
+
+    if let Some(version) = current.version() {
+        builder.version(version.clone());
+    }
+    if let Some(date) = date {
+        builder.date(date);
+    }
+
+    Ok((builder.build().map_err(Error::from)?, entries))
+}
+
+/// Keep the current entries which aren't removed on the other side, followed by the entries added
+/// on the other side.
+fn merge_entries(base: &[String], current: &[String], other: &[String]) -> Vec<String> {
+    let mut entries = current
+        .iter()
+        .filter(|entry| !base.contains(entry) || other.contains(entry))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for entry in other {
+        if !base.contains(entry) && !entries.contains(entry) {
+            entries.push(entry.clone());
+        }
+    }
+
+    entries
+}
+
+/// Get the base entries replaced on the side by the new entries at their position.
+fn edits<'a>(base: &'a [String], side: &'a [String]) -> HashMap<&'a String, &'a String> {
+    let mut edits = HashMap::new();
+    let mut removed = vec![];
+    let mut position = 0;
+
+    // Each base entry kept on the side closes the hunk of the removed entries before it
+    for entry in base.iter().map(Some).chain([None]) {
+        let kept = match entry {
+            Some(entry) => match side.iter().position(|e| e == entry) {
+                Some(idx) => Some(idx),
+                None => {
+                    removed.push(entry);
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        let end = kept.unwrap_or(side.len()).max(position);
+        let added = side[position..end].iter().filter(|e| !base.contains(e));
+        for (removed, added) in removed.drain(..).zip(added) {
+            edits.insert(removed, added);
+        }
+        position = kept.map_or(end, |idx| idx + 1);
+    }
+
+    edits
+}
+
+/// Take the side which changed the value, both sides changing it differently is a conflict.
+fn merge_value<T: PartialEq>(field: &str, base: Option<T>, current: T, other: T) -> Result<T> {
+    if current == other || base.as_ref() == Some(&other) {
+        return Ok(current);
+    }
+
+    if base.as_ref() == Some(&current) {
+        return Ok(other);
+    }
+
+    Err(eyre!("{field} is changed on both sides").into())
+}
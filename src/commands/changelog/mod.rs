@@ -1,17 +1,35 @@
 use clap::Subcommand;
 use serde::{Deserialize, Serialize};
 
-use crate::{context::Context, error::Result};
+use colored::Colorize;
 
+use crate::{context::Context, error::Result, workspace};
+
+pub(super) use self::release::ReleaseCmd;
 use self::{
-    format::FormatCmd, get::GetCmd, new::NewCmd, release::ReleaseCmd, validate::ValidateCmd,
+    aggregate::AggregateCmd,
+    convert::ConvertCmd,
+    export::ExportCmd,
+    format::FormatCmd,
+    get::GetCmd,
+    merge_driver::MergeDriverCmd,
+    new::NewCmd,
+    stats::StatsCmd,
+    validate::ValidateCmd,
+    yank::{UnyankCmd, YankCmd},
 };
 
+mod aggregate;
+mod convert;
+mod export;
 mod format;
 mod get;
+mod merge_driver;
 mod new;
 mod release;
+mod stats;
 mod validate;
+mod yank;
 
 #[derive(Subcommand, Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -26,16 +44,87 @@ pub(crate) enum Changelog {
     Format(FormatCmd),
     /// Create a new changelog
     New(NewCmd),
+    /// Mark a release as [YANKED]
+    Yank(YankCmd),
+    /// Remove the [YANKED] mark from a release
+    Unyank(UnyankCmd),
+    /// Convert a changelog of another format to the Keep a Changelog format
+    Convert(ConvertCmd),
+    /// Export a changelog as an HTML page, JSON document or Atom/RSS feed
+    Export(ExportCmd),
+    /// Aggregate the workspace packages changelogs into the root changelog
+    Aggregate(AggregateCmd),
+    /// Show the changelog statistics: release cadence, bumps and entries per category
+    Stats(StatsCmd),
+    /// Merge driver for git, merges the changelogs structurally (`%O %A %B`)
+    MergeDriver(MergeDriverCmd),
 }
 
 impl Changelog {
     pub(super) async fn run(self, ctx: &Context) -> Result<()> {
         match self {
-            Changelog::Validate(cmd) => cmd.run(ctx),
-            Changelog::Get(cmd) => cmd.run(ctx),
-            Changelog::Release(cmd) => cmd.run(ctx),
-            Changelog::Format(cmd) => cmd.run(ctx),
+            Changelog::Validate(cmd) => run_for_packages(ctx, |ctx| cmd.clone().run(ctx)),
+            Changelog::Get(cmd) => run_for_packages(ctx, |ctx| cmd.clone().run(ctx)),
+            Changelog::Release(cmd) => run_for_packages(ctx, |ctx| cmd.clone().run(ctx)),
+            Changelog::Format(cmd) => run_for_packages(ctx, |ctx| cmd.clone().run(ctx)),
             Changelog::New(cmd) => cmd.run(ctx),
+            Changelog::Yank(cmd) => cmd.run(ctx),
+            Changelog::Unyank(cmd) => cmd.run(ctx),
+            Changelog::Convert(cmd) => cmd.run(ctx),
+            Changelog::Export(cmd) => cmd.run(ctx),
+            Changelog::Aggregate(cmd) => cmd.run(ctx),
+            Changelog::Stats(cmd) => run_for_packages(ctx, |ctx| cmd.clone().run(ctx)),
+            Changelog::MergeDriver(cmd) => cmd.run(ctx),
+        }
+    }
+}
+
+/// Run the command for the changelog of each workspace package (or the selected one), falls back
+/// to the root changelog if the workspace path is not set or the packages have no changelogs.
+fn run_for_packages(ctx: &Context, run: impl Fn(&Context) -> Result<()>) -> Result<()> {
+    let packages = workspace::changelog_packages(ctx)?;
+
+    match packages.as_slice() {
+        [] => {
+            if ctx.workspace_path().is_some() {
+                ctx.debug("No packages changelogs found, using the root changelog");
+            }
+            run(ctx)
+        }
+        [package] => run(&ctx.with_package(package)),
+        _ => {
+            // The progress and the summary go to stderr to keep stdout for the command output,
+            // e.g. `get`
+            let ctx = ctx.with_stderr_log();
+            let mut results = vec![];
+
+            for package in &packages {
+                ctx.info(&format!("Package {}", package.name.bold()));
+                let result = run(&ctx.with_package(package));
+                if let Err(err) = &result {
+                    ctx.error(err.message());
+                }
+                results.push((package, result));
+            }
+
+            let name_width = packages.iter().map(|p| p.name.len()).max().unwrap_or(0);
+            let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+
+            let mut summary = String::from("Summary:");
+            for (package, result) in &results {
+                let status = match result {
+                    Ok(()) => "ok".green().bold().to_string(),
+                    Err(err) => format!("{} {}", "failed:".red().bold(), err.message().red()),
+                };
+                summary.push_str(&format!("\n  {:name_width$}  {status}", package.name));
+            }
+            ctx.info(&summary);
+
+            if failed > 0 {
+                return Err(format!("{failed} of {} packages failed", results.len()).into());
+            }
+
+            Ok(())
         }
     }
 }
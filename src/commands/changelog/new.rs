@@ -1,33 +1,136 @@
-use clap::Args;
-use keep_a_changelog::{changelog::ChangelogBuilder, ReleaseBuilder};
+use clap::{Args, ValueEnum};
+use keep_a_changelog::{changelog::ChangelogBuilder, ChangeKind, Changes, Release, ReleaseBuilder};
+use semver::Version;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    changelog_ext::Document,
     context::Context,
     error::{Error, Result},
 };
 
+#[derive(Clone, Copy, Debug, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum TagDate {
+    /// Date of the tag creation, commit date for the lightweight tags
+    #[default]
+    Tag,
+    /// Date of the tagged commit
+    Commit,
+}
+
 #[derive(Clone, Args, Debug, Serialize, Deserialize)]
-pub(crate) struct NewCmd;
+pub(crate) struct NewCmd {
+    /// Create a release for each semver git tag matching the tag prefix
+    #[arg(long)]
+    #[serde(default)]
+    from_tags: bool,
+    /// The date to use for the releases created from the git tags
+    #[arg(long, value_enum, default_value_t, requires = "from_tags")]
+    #[serde(default)]
+    date_from: TagDate,
+    /// Fill the releases created from the git tags with the commit subjects
+    #[arg(long, requires = "from_tags")]
+    #[serde(default)]
+    with_commits: bool,
+}
 
 impl NewCmd {
     pub(crate) fn run(self, ctx: &Context) -> Result<()> {
         ctx.debug("Creating new changelog");
 
         let unreleased = ReleaseBuilder::default().build().map_err(Error::from)?;
+        let mut releases = vec![unreleased];
+
+        if self.from_tags {
+            releases.extend(self.releases_from_tags(ctx)?);
+        }
 
         let changelog = ChangelogBuilder::default()
             .url(ctx.remote_url()?.to_owned())
-            .tag_prefix(ctx.tag_prefix())
+            .tag_prefix(ctx.tag_prefix()?)
             .head(ctx.head())
-            .releases(vec![unreleased])
+            .releases(releases)
             .build()
             .map_err(Error::from)?;
 
         ctx.debug("Saving new changelog");
-        changelog.save_to_file(ctx.changelog_path())?;
+        Document::from(changelog).save(ctx, ctx.changelog_path())?;
         ctx.success("New changelog created successfully");
 
         Ok(())
     }
+
+    fn releases_from_tags(&self, ctx: &Context) -> Result<Vec<Release>> {
+        let prefix = ctx.tag_prefix()?.unwrap_or_default();
+        let mut tags = ctx
+            .git()?
+            .tags()?
+            .into_iter()
+            .filter_map(|tag| {
+                let version = tag.name.strip_prefix(prefix.as_str())?.parse::<Version>();
+                version.ok().map(|version| (version, tag))
+            })
+            .collect::<Vec<_>>();
+
+        if tags.is_empty() {
+            return Err(format!("No semver git tags found with the \"{prefix}\" prefix").into());
+        }
+
+        ctx.debug(format!("Found {} semver git tag(s)", tags.len()).as_str());
+        tags.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut releases = vec![];
+        let mut previous: Option<&str> = None;
+
+        for (version, tag) in &tags {
+            let date = match self.date_from {
+                TagDate::Tag => tag.tag_date,
+                TagDate::Commit => tag.commit_date,
+            };
+
+            let mut changes = Changes::default();
+            if self.with_commits {
+                for subject in ctx.git()?.commit_subjects(previous, &tag.name)? {
+                    let (kind, change) = commit_change(&subject);
+                    changes.add(kind, change);
+                }
+            }
+
+            let release = Release::builder()
+                .version(version.clone())
+                .date(date)
+                .changes(changes)
+                .build()
+                .map_err(Error::from)?;
+
+            ctx.debug(format!("Created release [{version}] from tag {}", tag.name).as_str());
+            releases.push(release);
+            previous = Some(&tag.name);
+        }
+
+        // Releases are ordered by date, so the newest versions go first to keep the order of the
+        // releases made on the same day
+        releases.reverse();
+
+        Ok(releases)
+    }
+}
+
+/// Get the change kind and text of a commit subject, following the conventional commits types.
+fn commit_change(subject: &str) -> (ChangeKind, String) {
+    let Some((kind, text)) = subject.split_once(": ") else {
+        return (ChangeKind::Changed, subject.to_string());
+    };
+
+    let kind = kind
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('!');
+    match kind {
+        "feat" => (ChangeKind::Added, text.to_string()),
+        "fix" => (ChangeKind::Fixed, text.to_string()),
+        _ => (ChangeKind::Changed, subject.to_string()),
+    }
 }
@@ -1,43 +1,55 @@
-use chrono::Local;
 use clap::Args;
 use eyre::{eyre, OptionExt};
-use keep_a_changelog::{Changelog, Release};
+use keep_a_changelog::{NaiveDate, Release};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
-use crate::{changelog_ext::ChangelogExt, context::Context, error::Result};
+use crate::{changelog_ext::Document, context::Context, error::Result};
 
 #[derive(Clone, Args, Debug, Serialize, Deserialize)]
 pub(crate) struct ReleaseCmd {
     /// Release version
     version: String,
+    /// Release date in the `YYYY-MM-DD` format, if not provided, the current date will be used.
+    #[arg(short, long)]
+    date: Option<String>,
 }
 
 impl ReleaseCmd {
+    pub(crate) fn new(version: String, date: Option<String>) -> Self {
+        Self { version, date }
+    }
+
     pub(crate) fn run(self, ctx: &Context) -> Result<()> {
         let version: Version = self
             .version
             .parse()
             .map_err(|e| eyre!("Failed to parse version: {e}"))?;
-        let mut changelog = Changelog::from_ctx(ctx)?;
+        let date = match &self.date {
+            Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|e| eyre!("Failed to parse release date \"{date}\": {e}"))?,
+            None => ctx.today()?,
+        };
+        let mut changelog = Document::from_ctx(ctx)?;
         let unreleased = changelog
-            .get_unreleased_mut()
+            .get_unreleased()
             .ok_or_eyre("Unreleased section not found")?;
 
-        if unreleased.changes().is_empty() {
+        let entries = changelog.entries(unreleased);
+        if entries.is_empty() {
             return Err("No changes found in the unreleased section".into());
         }
 
         let release = Release::builder()
             .version(version.clone())
-            .date(Local::now().naive_local())
-            .changes(unreleased.changes().clone())
+            .date(date)
             .build()
             .map_err(|e| eyre!("Failed to build release: {e}"))?;
 
-        unreleased.empty_changes();
+        changelog.set_entries(&None, vec![])?;
         changelog.add_release(release);
-        changelog.save_to_file(ctx.changelog_path())?;
+        changelog.set_entries(&Some(version.clone()), entries)?;
+        changelog.save(ctx, ctx.changelog_path())?;
 
         ctx.success(&format!("Release [{}] added", version));
 
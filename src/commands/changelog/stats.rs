@@ -0,0 +1,328 @@
+use clap::Args;
+use colored::Colorize;
+use keep_a_changelog::Release;
+use serde::{Deserialize, Serialize};
+use serde_json::Map;
+
+use crate::{category::BumpLevel, changelog_ext::Document, context::Context, error::Result};
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub(crate) struct StatsCmd {
+    /// Print the statistics as a JSON document
+    #[arg(long)]
+    #[serde(default)]
+    json: bool,
+    /// Read the changelog at the git revision (e.g. a tag or a commit) instead of the working tree
+    #[arg(long)]
+    #[serde(default)]
+    rev: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Stats {
+    /// Number of the released versions, including the yanked ones
+    releases: usize,
+    yanked: usize,
+    cadence: Cadence,
+    bumps: Bumps,
+    last_release: Option<LastRelease>,
+    /// Number of the entries in the `[Unreleased]` section
+    unreleased: usize,
+    /// Number of the entries per category of each release, latest first
+    entries: Vec<ReleaseEntries>,
+}
+
+/// Days between the consecutive releases.
+#[derive(Debug, Serialize)]
+struct Cadence {
+    mean_days: Option<f64>,
+    median_days: Option<f64>,
+}
+
+/// Number of the releases by the bump from the previous version, the first release isn't a bump.
+#[derive(Debug, Default, Serialize)]
+struct Bumps {
+    major: usize,
+    minor: usize,
+    patch: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct LastRelease {
+    version: String,
+    date: String,
+    days_ago: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct ReleaseEntries {
+    version: String,
+    date: Option<String>,
+    /// Number of the entries by the lowercase category name, like in the `get` and `export` JSON
+    entries: Map<String, serde_json::Value>,
+}
+
+impl StatsCmd {
+    pub(crate) fn run(self, ctx: &Context) -> Result<()> {
+        let changelog = Document::from_rev(ctx, self.rev.as_deref())?;
+        let stats = stats(ctx, &changelog)?;
+
+        if self.json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&stats)
+                    .map_err(|e| format!("Failed to serialize stats: {e}"))?
+            );
+            return Ok(());
+        }
+
+        print(ctx, &stats);
+        Ok(())
+    }
+}
+
+fn stats(ctx: &Context, changelog: &Document) -> Result<Stats> {
+    let releases = changelog
+        .releases()
+        .iter()
+        .filter(|release| release.version().is_some())
+        .collect::<Vec<_>>();
+
+    let mut dates = releases
+        .iter()
+        .filter_map(|release| *release.date())
+        .collect::<Vec<_>>();
+    dates.sort();
+
+    let mut intervals = dates
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_days() as f64)
+        .collect::<Vec<_>>();
+    intervals.sort_by(f64::total_cmp);
+
+    let mut versions = releases
+        .iter()
+        .filter_map(|release| release.version().clone())
+        .collect::<Vec<_>>();
+    versions.sort();
+
+    let mut bumps = Bumps::default();
+    for pair in versions.windows(2) {
+        match BumpLevel::between(&pair[0], &pair[1]) {
+            BumpLevel::Major => bumps.major += 1,
+            BumpLevel::Minor => bumps.minor += 1,
+            BumpLevel::Patch => bumps.patch += 1,
+            BumpLevel::None => {}
+        }
+    }
+
+    let today = ctx.today()?;
+    let last_release = releases
+        .iter()
+        .filter_map(|release| Some((release.version().clone()?, (*release.date())?)))
+        .max_by(|(a_version, a_date), (b_version, b_date)| {
+            a_date.cmp(b_date).then_with(|| a_version.cmp(b_version))
+        })
+        .map(|(version, date)| LastRelease {
+            version: version.to_string(),
+            date: date.to_string(),
+            days_ago: (today - date).num_days(),
+        });
+
+    let unreleased = changelog
+        .get_unreleased()
+        .map(|release| {
+            count(changelog, release)
+                .iter()
+                .map(|(_, count)| count)
+                .sum()
+        })
+        .unwrap_or_default();
+
+    let entries = releases
+        .iter()
+        .map(|release| ReleaseEntries {
+            version: release
+                .version()
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            date: release.date().map(|date| date.to_string()),
+            entries: count(changelog, release)
+                .into_iter()
+                .map(|(category, count)| (category.to_lowercase(), count.into()))
+                .collect(),
+        })
+        .collect();
+
+    Ok(Stats {
+        releases: releases.len(),
+        yanked: releases.iter().filter(|release| *release.yanked()).count(),
+        cadence: Cadence {
+            mean_days: mean(&intervals),
+            median_days: median(&intervals),
+        },
+        bumps,
+        last_release,
+        unreleased,
+        entries,
+    })
+}
+
+fn print(ctx: &Context, stats: &Stats) {
+    let days =
+        |value: Option<f64>| value.map_or("n/a".to_string(), |days| format!("{days:.1} days"));
+
+    println!(
+        "{} {} ({} yanked)",
+        "Releases:".bold(),
+        stats.releases,
+        stats.yanked
+    );
+    println!(
+        "{} {} mean, {} median",
+        "Cadence:".bold(),
+        days(stats.cadence.mean_days),
+        days(stats.cadence.median_days)
+    );
+    println!(
+        "{} {} major, {} minor, {} patch",
+        "Bumps:".bold(),
+        stats.bumps.major,
+        stats.bumps.minor,
+        stats.bumps.patch
+    );
+    match &stats.last_release {
+        Some(release) => println!(
+            "{} {} on {}, {} days ago",
+            "Last release:".bold(),
+            release.version,
+            release.date,
+            release.days_ago
+        ),
+        None => println!("{} n/a", "Last release:".bold()),
+    }
+    println!("{} {}", "Unreleased entries:".bold(), stats.unreleased);
+
+    if !stats.entries.is_empty() {
+        println!();
+        print!("{}", table(ctx, stats));
+    }
+}
+
+/// Render the table of the number of the entries per category of each release, the categories
+/// without entries are left out.
+fn table(ctx: &Context, stats: &Stats) -> String {
+    let categories = ctx
+        .categories()
+        .names()
+        .into_iter()
+        .filter(|name| {
+            stats
+                .entries
+                .iter()
+                .any(|release| release.count(name).is_some())
+        })
+        .collect::<Vec<_>>();
+    let version_width = stats
+        .entries
+        .iter()
+        .map(|release| release.version.len())
+        .chain(["Version".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut header = format!("{:version_width$}  {:10}", "Version", "Date");
+    for category in &categories {
+        header.push_str(&format!("  {category}"));
+    }
+    let mut table = format!("{}\n", header.bold());
+
+    for release in &stats.entries {
+        let date = release.date.as_deref().unwrap_or("-");
+        let mut line = format!("{:version_width$}  {date:10}", release.version);
+        for category in &categories {
+            let count = release.count(category).unwrap_or_default();
+            line.push_str(&format!("  {count:>width$}", width = category.len()));
+        }
+        table.push_str(&format!("{line}\n"));
+    }
+
+    table
+}
+
+impl ReleaseEntries {
+    /// Get the number of the entries of the category, by its name in any case.
+    fn count(&self, category: &str) -> Option<u64> {
+        self.entries
+            .get(&category.to_lowercase())
+            .and_then(|count| count.as_u64())
+    }
+}
+
+/// Count the entries of the release by the category name.
+fn count(changelog: &Document, release: &Release) -> Vec<(String, usize)> {
+    changelog
+        .entries(release)
+        .into_iter()
+        .map(|(category, entries)| (category, entries.len()))
+        .collect()
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Get the median of the sorted values.
+fn median(values: &[f64]) -> Option<f64> {
+    let middle = values.len() / 2;
+
+    match values.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((values[middle - 1] + values[middle]) / 2.0),
+        _ => Some(values[middle]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stats, table};
+    use crate::{changelog_ext::Document, config::Config, context::Context};
+
+    #[test]
+    fn table_has_category_columns() {
+        let ctx = Context::from_config(Config {
+            remote_url: Some("https://github.com/owner/repo".to_string()),
+            ..Config::default()
+        });
+        let changelog = Document::from_markdown(
+            &ctx,
+            "# Changelog
+
+## [1.1.0] - 2024-02-01
+
+### Added
+
+- One
+- Two
+
+## [1.0.0] - 2024-01-01
+
+### Fixed
+
+- Three
+",
+        )
+        .unwrap();
+
+        let table = table(&ctx, &stats(&ctx, &changelog).unwrap());
+        let lines = table.lines().collect::<Vec<_>>();
+        assert!(lines[0].contains("Version  Date        Added  Fixed"));
+        assert_eq!(lines[1], "1.1.0    2024-02-01      2      0");
+        assert_eq!(lines[2], "1.0.0    2024-01-01      0      1");
+    }
+}
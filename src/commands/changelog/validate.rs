@@ -1,15 +1,19 @@
 use clap::Args;
-use keep_a_changelog::Changelog;
 use serde::{Deserialize, Serialize};
 
-use crate::{changelog_ext::ChangelogExt, context::Context, error::Result};
+use crate::{changelog_ext::Document, context::Context, error::Result};
 
 #[derive(Clone, Args, Debug, Serialize, Deserialize)]
-pub(crate) struct ValidateCmd;
+pub(crate) struct ValidateCmd {
+    /// Read the changelog at the git revision (e.g. a tag or a commit) instead of the working tree
+    #[arg(long)]
+    #[serde(default)]
+    rev: Option<String>,
+}
 
 impl ValidateCmd {
     pub(crate) fn run(self, ctx: &Context) -> Result<()> {
-        Changelog::from_ctx(ctx)?;
+        Document::from_rev(ctx, self.rev.as_deref())?;
         ctx.success("Changelog is valid");
         Ok(())
     }
@@ -0,0 +1,48 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use crate::{changelog_ext::Document, context::Context, error::Result};
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub(crate) struct YankCmd {
+    /// The version of the release to mark as yanked
+    version: String,
+}
+
+impl YankCmd {
+    pub(crate) fn run(self, ctx: &Context) -> Result<()> {
+        set_yanked(ctx, &self.version, true)
+    }
+}
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub(crate) struct UnyankCmd {
+    /// The version of the yanked release to unmark
+    version: String,
+}
+
+impl UnyankCmd {
+    pub(crate) fn run(self, ctx: &Context) -> Result<()> {
+        set_yanked(ctx, &self.version, false)
+    }
+}
+
+fn set_yanked(ctx: &Context, version: &str, yanked: bool) -> Result<()> {
+    let mut changelog = Document::from_ctx(ctx)?;
+    let release = changelog
+        .find_release_mut(version.to_string())?
+        .ok_or_else(|| format!("{version} release not found"))?;
+
+    let action = if yanked { "yanked" } else { "unyanked" };
+
+    if *release.yanked() == yanked {
+        ctx.info(&format!("Release [{version}] is already {action}"));
+        return Ok(());
+    }
+
+    *release = release.clone().set_yanked(yanked);
+    changelog.save(ctx, ctx.changelog_path())?;
+
+    ctx.success(&format!("Release [{version}] {action}"));
+    Ok(())
+}
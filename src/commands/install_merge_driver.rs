@@ -0,0 +1,56 @@
+use std::{fs, path::Path};
+
+use clap::Args;
+use eyre::eyre;
+use serde::{Deserialize, Serialize};
+
+use crate::{context::Context, error::Result};
+
+const DRIVER_NAME: &str = "rlx-changelog";
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub struct InstallMergeDriverCmd {
+    /// The command to run `rlx` with in the merge driver
+    #[arg(long, default_value = "npx rlx")]
+    command: String,
+}
+
+impl InstallMergeDriverCmd {
+    pub async fn run(&self, ctx: &Context) -> Result<()> {
+        let file_name = Path::new(ctx.changelog_path())
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "CHANGELOG.md".to_string());
+        let attribute = format!("{file_name} merge={DRIVER_NAME}");
+
+        let path = Path::new(ctx.root()).join(".gitattributes");
+        let gitattributes = fs::read_to_string(&path).unwrap_or_default();
+
+        if gitattributes.lines().any(|line| line.trim() == attribute) {
+            ctx.info(".gitattributes already has the merge driver entry");
+        } else {
+            let mut contents = gitattributes;
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            contents.push_str(&attribute);
+            contents.push('\n');
+
+            fs::write(&path, contents).map_err(|e| eyre!("Failed to write .gitattributes: {e}"))?;
+            ctx.debug(format!("Added \"{attribute}\" to .gitattributes").as_str());
+        }
+
+        let git = ctx.git()?;
+        git.set_config(
+            &format!("merge.{DRIVER_NAME}.name"),
+            "rlx changelog merge driver",
+        )?;
+        git.set_config(
+            &format!("merge.{DRIVER_NAME}.driver"),
+            &format!("{} changelog merge-driver %O %A %B", self.command),
+        )?;
+
+        ctx.success("Changelog merge driver installed successfully");
+        Ok(())
+    }
+}
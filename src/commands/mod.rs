@@ -1,6 +1,17 @@
 pub use top_level::Commands;
 
 mod changelog;
+mod install_merge_driver;
+mod release;
 mod rsc;
 mod top_level;
 mod version;
+
+/// Escape the text for the XML and HTML documents, e.g. the changelog feeds and the JUnit report.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -0,0 +1,304 @@
+use std::{fs, path::Path, str::FromStr};
+
+use clap::Args;
+use eyre::{eyre, OptionExt};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    category::BumpLevel,
+    changelog_ext::Document,
+    context::{Context, STDIO_PATH},
+    error::Result,
+};
+
+use super::{changelog::ReleaseCmd as ChangelogReleaseCmd, version::ApplyCmd};
+
+const DEFAULT_COMMIT_MESSAGE: &str = "chore: release {version}";
+const DEFAULT_TAG_MESSAGE: &str = "Release {tag}";
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub struct ReleaseCmd {
+    /// The release version, or the part of the latest released version to bump (`major`, `minor`
+    /// or `patch`), or `auto` to infer the bump from the categories of the [Unreleased] changes.
+    /// The explicit version must be greater than the latest released version
+    version: String,
+    /// Release date in the `YYYY-MM-DD` format, if not provided, the current date will be used.
+    #[arg(short, long)]
+    date: Option<String>,
+    /// The release commit message template, `{version}` and `{tag}` are replaced with the release
+    /// version and tag.
+    /// If not provided, the `release.commit_message` config option or `chore: release {version}`
+    /// will be used.
+    #[arg(short, long)]
+    message: Option<String>,
+    /// The release tag message template, `{version}` and `{tag}` are replaced with the release
+    /// version and tag.
+    /// If not provided, the `release.tag_message` config option or `Release {tag}` will be used.
+    #[arg(long)]
+    tag_message: Option<String>,
+    /// Don't commit the release changes, implies `--no-tag`.
+    #[arg(long)]
+    no_commit: bool,
+    /// Don't create the release tag.
+    #[arg(long)]
+    no_tag: bool,
+}
+
+/// Optional steps of the release.
+struct Steps {
+    apply_version: bool,
+    commit: bool,
+    tag: bool,
+}
+
+enum Bump {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl FromStr for Bump {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "major" => Ok(Self::Major),
+            "minor" => Ok(Self::Minor),
+            "patch" => Ok(Self::Patch),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Bump {
+    fn apply(&self, version: &Version) -> Version {
+        match self {
+            Bump::Major => Version::new(version.major + 1, 0, 0),
+            Bump::Minor => Version::new(version.major, version.minor + 1, 0),
+            Bump::Patch => Version::new(version.major, version.minor, version.patch + 1),
+        }
+    }
+}
+
+/// Infer the bump from the highest bump level of the categories of the [Unreleased] changes.
+fn infer_bump(ctx: &Context, changelog: &Document) -> Result<Bump> {
+    let entries = changelog
+        .get_unreleased()
+        .map(|release| changelog.entries(release))
+        .unwrap_or_default();
+    let level = ctx
+        .categories()
+        .bump_level(entries.iter().map(|(category, _)| category.as_str()));
+
+    ctx.debug(format!("Inferred bump level: {level:?}").as_str());
+
+    match level {
+        BumpLevel::Major => Ok(Bump::Major),
+        BumpLevel::Minor => Ok(Bump::Minor),
+        BumpLevel::Patch => Ok(Bump::Patch),
+        BumpLevel::None => Err("[Unreleased] section has no changes requiring a release".into()),
+    }
+}
+
+impl ReleaseCmd {
+    pub async fn run(&self, ctx: &Context) -> Result<()> {
+        if ctx.changelog_path() == STDIO_PATH {
+            return Err("The release can't be made with the changelog read from stdin".into());
+        }
+
+        let commit = !self.no_commit;
+        let tag_release = commit && !self.no_tag;
+
+        let version = self.resolve_version(ctx)?;
+        let tag = format!("{}{version}", ctx.tag_prefix()?.unwrap_or_default());
+
+        ctx.debug(format!("Release version: {version}, tag: {tag}").as_str());
+        self.validate(ctx, &tag, tag_release)?;
+
+        let packages = ApplyCmd::package_paths(ctx)?
+            .into_iter()
+            .filter(|path| Path::new(path).exists())
+            .collect::<Vec<_>>();
+        let apply_version = !packages.is_empty();
+        let mut paths = vec![ctx.changelog_path().to_string()];
+        paths.extend(packages);
+
+        let snapshot = Snapshot::take(&paths)?;
+        let head = if commit {
+            Some(ctx.git()?.head_commit()?)
+        } else {
+            None
+        };
+
+        let steps = Steps {
+            apply_version,
+            commit,
+            tag: tag_release,
+        };
+
+        if let Err(err) = self.release(ctx, &version, &tag, &paths, &steps) {
+            ctx.error("Release failed, rolling back changes");
+            self.rollback(ctx, &snapshot, head, &paths);
+            return Err(err);
+        }
+
+        ctx.success(&format!("Release [{version}] is done"));
+        Ok(())
+    }
+
+    fn resolve_version(&self, ctx: &Context) -> Result<Version> {
+        let bump = self.version.parse::<Bump>().ok();
+        let auto = self.version.eq_ignore_ascii_case("auto");
+
+        let changelog = Document::from_ctx(ctx)?;
+        let latest = changelog
+            .releases()
+            .iter()
+            .filter(|r| r.date().is_some())
+            .find_map(|r| r.version().clone());
+
+        if let Some(latest) = &latest {
+            ctx.debug(format!("Latest released version: {latest}").as_str());
+        }
+
+        if bump.is_none() && !auto {
+            let version = self
+                .version
+                .parse::<Version>()
+                .map_err(|e| eyre!("Failed to parse version: {e}"))?;
+
+            return match latest {
+                Some(latest) if version <= latest => Err(format!(
+                    "Version {version} must be greater than the latest released version {latest}"
+                )
+                .into()),
+                _ => Ok(version),
+            };
+        }
+
+        let bump = match bump {
+            Some(bump) => bump,
+            None => infer_bump(ctx, &changelog)?,
+        };
+
+        Ok(bump.apply(&latest.unwrap_or_else(|| Version::new(0, 0, 0))))
+    }
+
+    fn validate(&self, ctx: &Context, tag: &str, tag_release: bool) -> Result<()> {
+        ctx.debug("Validating release");
+
+        let changelog = Document::from_ctx(ctx)?;
+        let unreleased = changelog
+            .get_unreleased()
+            .ok_or_eyre("Unreleased section not found")?;
+
+        if changelog.entries(unreleased).is_empty() {
+            return Err("No changes found in the unreleased section".into());
+        }
+
+        if tag_release && ctx.git()?.tag_exists(tag)? {
+            return Err(format!("Tag {tag} already exists").into());
+        }
+
+        Ok(())
+    }
+
+    fn release(
+        &self,
+        ctx: &Context,
+        version: &Version,
+        tag: &str,
+        paths: &[String],
+        steps: &Steps,
+    ) -> Result<()> {
+        ChangelogReleaseCmd::new(version.to_string(), self.date.clone()).run(ctx)?;
+
+        if steps.apply_version {
+            ApplyCmd::new(version.to_string()).run(ctx)?;
+        } else {
+            ctx.info("No package.json found, skipping version apply");
+        }
+
+        if steps.commit {
+            let template = self
+                .message
+                .clone()
+                .or_else(|| ctx.release_config().commit_message.clone())
+                .unwrap_or_else(|| DEFAULT_COMMIT_MESSAGE.to_string());
+            let message = render_template(&template, version, tag);
+
+            ctx.git()?.commit(&message, paths)?;
+            ctx.success(&format!("Release changes committed: \"{message}\""));
+        }
+
+        if steps.tag {
+            let template = self
+                .tag_message
+                .clone()
+                .or_else(|| ctx.release_config().tag_message.clone())
+                .unwrap_or_else(|| DEFAULT_TAG_MESSAGE.to_string());
+
+            ctx.git()?
+                .create_tag(tag, &render_template(&template, version, tag))?;
+            ctx.success(&format!("Tag {tag} created"));
+        }
+
+        Ok(())
+    }
+
+    fn rollback(&self, ctx: &Context, snapshot: &Snapshot, head: Option<String>, paths: &[String]) {
+        if let Some(head) = head {
+            if let Err(err) = ctx.git().and_then(|git| git.reset(&head, paths)) {
+                ctx.error(&format!("Failed to reset release commit: {err}"));
+            }
+        }
+
+        if let Err(err) = snapshot.restore() {
+            ctx.error(&format!("Failed to restore files: {err}"));
+        }
+    }
+}
+
+/// Contents of the files before the release, used to roll back the changes on failure.
+struct Snapshot {
+    files: Vec<(String, Option<Vec<u8>>)>,
+}
+
+impl Snapshot {
+    fn take(paths: &[String]) -> Result<Self> {
+        let mut files = vec![];
+
+        for path in paths {
+            let contents = match Path::new(path).exists() {
+                true => Some(fs::read(path).map_err(|e| eyre!("Failed to read {path}: {e}"))?),
+                false => None,
+            };
+            files.push((path.clone(), contents));
+        }
+
+        Ok(Self { files })
+    }
+
+    fn restore(&self) -> eyre::Result<()> {
+        for (path, contents) in &self.files {
+            match contents {
+                Some(contents) => {
+                    fs::write(path, contents).map_err(|e| eyre!("Failed to restore {path}: {e}"))?
+                }
+                None if Path::new(path).exists() => {
+                    fs::remove_file(path).map_err(|e| eyre!("Failed to remove {path}: {e}"))?
+                }
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn render_template(template: &str, version: &Version, tag: &str) -> String {
+    template
+        .replace("{version}", &version.to_string())
+        .replace("{tag}", tag)
+}
@@ -0,0 +1,609 @@
+use std::{fs::File, path::Path};
+
+use colored::*;
+
+use eyre::{eyre, Context as _Context, OptionExt};
+use keep_a_changelog::Version;
+use once_cell::unsync::OnceCell;
+use regex::Regex;
+
+use crate::{
+    category::BumpLevel,
+    changelog_ext::Document,
+    config::{BreakingBump, CheckLevel},
+    context::Context,
+    error::{Error, Result},
+    workspace::Package,
+};
+
+/// Marker of the breaking change entries if the `rsc.semver.breaking_markers` option is not set.
+const DEFAULT_BREAKING_MARKER: &str = "BREAKING";
+
+/// Pattern of the release branches if the `rsc.release_branch` config option is not set.
+const DEFAULT_RELEASE_BRANCH: &str = "^(main|master)$";
+
+/// Release checked by the `rsc` command, of the whole repository or of a workspace package.
+pub(super) struct Candidate {
+    pub version: String,
+    pub date_tolerance: Option<u32>,
+    /// Name of the workspace package, `None` for the repository.
+    pub package: Option<String>,
+    /// Directory of the `package.json` file.
+    dir: String,
+    changelog_path: String,
+    changelog: OnceCell<Document>,
+    /// Context of the release, the package one uses the package tags.
+    pub ctx: Context,
+}
+
+impl Candidate {
+    /// Get the release of the repository.
+    pub fn new(ctx: &Context, version: String, date_tolerance: Option<u32>) -> Self {
+        Self {
+            version,
+            date_tolerance,
+            package: None,
+            dir: ctx.root().to_string(),
+            changelog_path: ctx.changelog_path().to_string(),
+            changelog: OnceCell::new(),
+            ctx: ctx.clone(),
+        }
+    }
+
+    /// Get the release of the workspace package, the root changelog is used if the package has
+    /// no changelog.
+    pub fn for_package(&self, package: Package) -> Self {
+        let changelog_path = package.changelog_path(&self.ctx);
+        let changelog_path = match Path::new(&changelog_path).exists() {
+            true => changelog_path,
+            false => self.changelog_path.clone(),
+        };
+
+        Self {
+            version: self.version.clone(),
+            date_tolerance: self.date_tolerance,
+            ctx: self.ctx.with_tag_package(package.name.clone()),
+            package: Some(package.name),
+            dir: package.dir,
+            changelog_path,
+            changelog: OnceCell::new(),
+        }
+    }
+
+    /// Get the release tag name.
+    fn tag(&self) -> Result<String> {
+        Ok(format!(
+            "{}{}",
+            self.ctx.tag_prefix()?.unwrap_or_default(),
+            self.version
+        ))
+    }
+
+    /// Get the changelog path with the changelog, it's parsed once for all the checks.
+    fn changelog(&self) -> Result<(&str, &Document)> {
+        let changelog = self
+            .changelog
+            .get_or_try_init(|| Document::from_path(&self.ctx, &self.changelog_path))?;
+
+        Ok((&self.changelog_path, changelog))
+    }
+}
+
+/// Rule of the release sanity check.
+pub(super) trait Check {
+    /// Name of the check, used to set its level in the `rsc.checks` config option.
+    fn name(&self) -> &'static str;
+    /// Level of the check if it's not configured, the optional checks are `off` by default.
+    fn default_level(&self) -> CheckLevel {
+        CheckLevel::Error
+    }
+    /// Whether the check is run for each workspace package rather than once for the repository.
+    fn per_package(&self) -> bool {
+        false
+    }
+    fn run(&self, ctx: &Context, release: &Candidate) -> Result<()>;
+}
+
+/// Get all the checks in the order they are run.
+pub(super) fn checks() -> Vec<Box<dyn Check>> {
+    vec![
+        Box::new(Semver),
+        Box::new(PackageVersion),
+        Box::new(ChangelogRelease),
+        Box::new(ChangelogDate),
+        Box::new(ChangelogLinks),
+        Box::new(SemverPolicy),
+        Box::new(GitClean),
+        Box::new(GitTag),
+        Box::new(GitTagAnnotated),
+        Box::new(GitBranch),
+        Box::new(GitUpstream),
+    ]
+}
+
+/// The release version is a valid semver version.
+struct Semver;
+
+impl Check for Semver {
+    fn name(&self) -> &'static str {
+        "semver"
+    }
+
+    fn run(&self, ctx: &Context, release: &Candidate) -> Result<()> {
+        semver::Version::parse(&release.version).map_err(|e| {
+            format!("Release version is not compatible with semantic versioning: {e}")
+        })?;
+
+        ctx.success("Release version is compatible with semantic versioning");
+        Ok(())
+    }
+}
+
+/// The `package.json` version equals the release version.
+struct PackageVersion;
+
+impl Check for PackageVersion {
+    fn name(&self) -> &'static str {
+        "package_version"
+    }
+
+    fn per_package(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &Context, release: &Candidate) -> Result<()> {
+        validate_package_version(ctx, &release.version, &release.dir)
+    }
+}
+
+/// The release is the latest not yanked release of the changelog.
+struct ChangelogRelease;
+
+impl Check for ChangelogRelease {
+    fn name(&self) -> &'static str {
+        "changelog_release"
+    }
+
+    fn per_package(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &Context, release: &Candidate) -> Result<()> {
+        let (path, changelog) = release.changelog()?;
+        let err_msg = format!("\"## [{}]\" is absent in {path}", release.version);
+
+        let latest = changelog
+            .releases()
+            .iter()
+            .find(|r| r.version().is_some() && !r.yanked())
+            .ok_or_else(|| Error::from(err_msg.clone()))?;
+        let latest_version = latest
+            .version()
+            .as_ref()
+            .map(|v| v.to_string())
+            .ok_or_eyre("Failed to get latest release version")?;
+
+        ctx.debug(format!("Latest release version of {path}: {latest_version}").as_str());
+
+        if latest_version != release.version {
+            return Err(format!(
+                "\"## [{}]\" is not the latest release in {path}, the latest release is {latest_version}",
+                release.version
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// The release date equals the current date, within the date tolerance.
+struct ChangelogDate;
+
+impl Check for ChangelogDate {
+    fn name(&self) -> &'static str {
+        "changelog_date"
+    }
+
+    fn per_package(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &Context, release: &Candidate) -> Result<()> {
+        let today = ctx.today()?;
+        let tolerance = release
+            .date_tolerance
+            .or(ctx.rsc_config().date_tolerance)
+            .unwrap_or_default();
+
+        let (path, changelog) = release.changelog()?;
+        let date = changelog
+            .find_release(release.version.clone())?
+            .ok_or_else(|| format!("\"## [{}]\" is absent in {path}", release.version))?
+            .date()
+            .ok_or_eyre("Failed to get release date")?;
+
+        ctx.debug(format!("Release date in {path}: {date}").as_str());
+
+        let days_diff = (today - date).num_days().unsigned_abs();
+        if days_diff > u64::from(tolerance) {
+            return Err(format!(
+                "Release date {date} in {path} differs from today's date {} by {days_diff} day(s), tolerance is {tolerance} day(s)",
+                today.format("%Y-%m-%d")
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// The anchors legend of the changelog has the valid compare links of all the releases.
+struct ChangelogLinks;
+
+impl Check for ChangelogLinks {
+    fn name(&self) -> &'static str {
+        "changelog_links"
+    }
+
+    fn per_package(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &Context, release: &Candidate) -> Result<()> {
+        let repo_url = ctx.remote_url()?.to_owned();
+
+        let (path, changelog) = release.changelog()?;
+        let links = changelog
+            .links()
+            .iter()
+            .filter(|l| l.url().contains(&repo_url))
+            .collect::<Vec<_>>();
+
+        let mut anchors = vec![];
+        let mut invalid_anchors = false;
+
+        for release in changelog.releases() {
+            let link = changelog
+                .release_link(ctx, release)?
+                .ok_or_eyre("Failed to get compare link")?;
+
+            let release_version = release.version();
+
+            if let Some(version) = release_version {
+                anchors.push(fmt_anchor(&version.to_string(), link.url.clone()));
+            } else {
+                anchors.push(fmt_anchor("Unreleased", link.url.clone()));
+            }
+
+            if !invalid_anchors {
+                invalid_anchors = !links.iter().any(|l| {
+                    let url_match = *l.url() == link.url;
+                    let anchor = l.anchor();
+                    let anchor_match = match release_version {
+                        Some(version) => l.anchor() == &version.to_string(),
+                        None => anchor == "Unreleased",
+                    };
+
+                    url_match && anchor_match
+                });
+            }
+        }
+
+        if invalid_anchors {
+            return Err(Error::new_fmt(format!(
+                "{}\n{}",
+                format!("The anchors legend of {path} is invalid, should be: ")
+                    .red()
+                    .bold(),
+                anchors.join("\n").red()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The release bump matches its changes: the breaking changes require a major bump, the releases
+/// with only patch-level changes (e.g. `Fixed`) can't be a major bump, and the version is greater
+/// than the previous release one. Before 1.0.0 the required bumps are shifted down by default, so
+/// the breaking changes require a minor bump and the patch-level changes can't be a minor bump.
+struct SemverPolicy;
+
+impl Check for SemverPolicy {
+    fn name(&self) -> &'static str {
+        "semver_policy"
+    }
+
+    fn per_package(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &Context, release: &Candidate) -> Result<()> {
+        let version = Version::parse(&release.version)
+            .map_err(|e| format!("Release version is not a valid semver version: {e}"))?;
+        let cfg = &ctx.rsc_config().semver;
+        let markers = cfg
+            .breaking_markers
+            .clone()
+            .unwrap_or_else(|| vec![DEFAULT_BREAKING_MARKER.to_string()]);
+
+        let (path, changelog) = release.changelog()?;
+        let releases = changelog.releases();
+        let position = releases
+            .iter()
+            .position(|r| r.version().as_ref() == Some(&version))
+            .ok_or_else(|| format!("\"## [{version}]\" is absent in {path}"))?;
+
+        let Some(previous) = releases[position + 1..]
+            .iter()
+            .filter(|r| !r.yanked())
+            .find_map(|r| r.version().clone())
+        else {
+            ctx.debug(format!("{version} is the first release of {path}").as_str());
+            return Ok(());
+        };
+
+        let bump = BumpLevel::between(&previous, &version);
+        if bump == BumpLevel::None {
+            return Err(format!(
+                "Release version {version} is not greater than the previous release version {previous} in {path}"
+            )
+            .into());
+        }
+
+        let entries = changelog.entries(&releases[position]);
+        let breaking = entries.iter().any(|(_, entries)| {
+            entries
+                .iter()
+                .any(|entry| markers.iter().any(|marker| entry.contains(marker.as_str())))
+        });
+        let mut required = ctx
+            .categories()
+            .bump_level(entries.iter().map(|(category, _)| category.as_str()));
+        if breaking {
+            required = BumpLevel::Major;
+        }
+
+        // Before 1.0.0 the breaking changes are released as minor bumps by default
+        let pre_1_0_breaking = cfg.pre_1_0_breaking_bump.unwrap_or(BreakingBump::Minor);
+        let (major, patch_max) = match (previous.major, pre_1_0_breaking) {
+            (0, BreakingBump::Minor) => (BumpLevel::Minor, BumpLevel::Patch),
+            _ => (BumpLevel::Major, BumpLevel::Minor),
+        };
+
+        ctx.debug(
+            format!("Release {version} bump from {previous}: {bump:?}, required: {required:?}")
+                .as_str(),
+        );
+
+        if required == BumpLevel::Major && bump < major {
+            return Err(format!(
+                "Release {version} has breaking changes, but it's a {} bump from {previous} in {path}",
+                bump_name(bump)
+            )
+            .into());
+        }
+
+        if required <= BumpLevel::Patch && bump > patch_max {
+            return Err(format!(
+                "Release {version} has only patch-level changes, but it's a {} bump from {previous} in {path}",
+                bump_name(bump)
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// The working tree has no uncommitted changes.
+struct GitClean;
+
+impl Check for GitClean {
+    fn name(&self) -> &'static str {
+        "git_clean"
+    }
+
+    fn default_level(&self) -> CheckLevel {
+        CheckLevel::Off
+    }
+
+    fn run(&self, ctx: &Context, _release: &Candidate) -> Result<()> {
+        let paths = ctx.git()?.dirty_paths()?;
+        if !paths.is_empty() {
+            return Err(
+                format!("Working tree has uncommitted changes: {}", paths.join(", ")).into(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The release tag exists and points at the `HEAD`.
+struct GitTag;
+
+impl Check for GitTag {
+    fn name(&self) -> &'static str {
+        "git_tag"
+    }
+
+    fn default_level(&self) -> CheckLevel {
+        CheckLevel::Off
+    }
+
+    fn run(&self, ctx: &Context, release: &Candidate) -> Result<()> {
+        let tag = release.tag()?;
+        let commit = ctx
+            .git()?
+            .rev_commit(&format!("refs/tags/{tag}"))?
+            .ok_or_else(|| format!("Tag {tag} doesn't exist"))?;
+        let head = ctx.git()?.head_commit()?;
+
+        if commit != head {
+            return Err(format!("Tag {tag} points at {commit}, not at HEAD ({head})").into());
+        }
+
+        Ok(())
+    }
+}
+
+/// The release tag is annotated rather than lightweight.
+struct GitTagAnnotated;
+
+impl Check for GitTagAnnotated {
+    fn name(&self) -> &'static str {
+        "git_tag_annotated"
+    }
+
+    fn default_level(&self) -> CheckLevel {
+        CheckLevel::Off
+    }
+
+    fn run(&self, ctx: &Context, release: &Candidate) -> Result<()> {
+        let tag = release.tag()?;
+        if !ctx.git()?.tag_exists(&tag)? {
+            return Err(format!("Tag {tag} doesn't exist").into());
+        }
+
+        if !ctx.git()?.tag_is_annotated(&tag)? {
+            return Err(format!("Tag {tag} is lightweight, expected an annotated tag").into());
+        }
+
+        Ok(())
+    }
+}
+
+/// The current branch matches the `rsc.release_branch` pattern.
+struct GitBranch;
+
+impl Check for GitBranch {
+    fn name(&self) -> &'static str {
+        "git_branch"
+    }
+
+    fn default_level(&self) -> CheckLevel {
+        CheckLevel::Off
+    }
+
+    fn run(&self, ctx: &Context, _release: &Candidate) -> Result<()> {
+        let pattern = ctx
+            .rsc_config()
+            .release_branch
+            .as_deref()
+            .unwrap_or(DEFAULT_RELEASE_BRANCH);
+        let regex = Regex::new(pattern)
+            .map_err(|e| eyre!("Invalid release branch pattern \"{pattern}\": {e}"))?;
+
+        let branch = ctx
+            .git()?
+            .current_branch()?
+            .ok_or_else(|| format!("HEAD is detached, expected a branch matching \"{pattern}\""))?;
+
+        if !regex.is_match(&branch) {
+            return Err(format!(
+                "Branch {branch} doesn't match the release branch pattern \"{pattern}\""
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// The `HEAD` has all the commits of its upstream branch.
+struct GitUpstream;
+
+impl Check for GitUpstream {
+    fn name(&self) -> &'static str {
+        "git_upstream"
+    }
+
+    fn default_level(&self) -> CheckLevel {
+        CheckLevel::Off
+    }
+
+    fn run(&self, ctx: &Context, _release: &Candidate) -> Result<()> {
+        let behind = ctx
+            .git()?
+            .commits_behind_upstream()?
+            .ok_or("Current branch has no upstream branch")?;
+
+        if behind > 0 {
+            return Err(format!("HEAD is {behind} commit(s) behind its upstream branch").into());
+        }
+
+        Ok(())
+    }
+}
+
+fn bump_name(bump: BumpLevel) -> &'static str {
+    match bump {
+        BumpLevel::Major => "major",
+        BumpLevel::Minor => "minor",
+        BumpLevel::Patch => "patch",
+        BumpLevel::None => "no",
+    }
+}
+
+struct PackageMetadata {
+    version: String,
+    name: String,
+}
+
+fn validate_package_version(ctx: &Context, release_version: &str, dir: &str) -> Result<()> {
+    let PackageMetadata { version, name } = get_package_metadata(ctx, dir)?;
+    let valid = release_version == version;
+
+    if !valid {
+        return Err(Error::new_fmt(format!(
+            "{}{}{}{}{}{}",
+            "Release version of the ".red(),
+            name.clone().red().bold(),
+            " is invalid, expected: ".red(),
+            release_version.red().bold(),
+            ", actual: ".red(),
+            version.red().bold()
+        )));
+    }
+
+    ctx.success_fmt(&format!(
+        "{}{}{}",
+        "Release version of the ".green(),
+        name.clone().green().bold(),
+        " is valid".green()
+    ));
+    Ok(())
+}
+
+fn get_package_metadata(ctx: &Context, dir: &str) -> Result<PackageMetadata> {
+    let path = format!("{dir}/package.json");
+
+    ctx.debug(format!("Reading package.json file from path {path}").as_str());
+
+    let file = File::open(
+        Path::new(path.as_str())
+            .canonicalize()
+            .wrap_err_with(|| "Failed to build package.json file path")?,
+    )
+    .wrap_err_with(|| "Failed to open package.json file")?;
+    let json: serde_json::Value =
+        serde_json::from_reader(file).wrap_err_with(|| "Failed to create json from reader")?;
+    let version = json
+        .get("version")
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string())
+        .ok_or_eyre("No version found in package.json")?;
+    let name = json
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string())
+        .ok_or_eyre("No name found in package.json")?;
+
+    Ok(PackageMetadata { version, name })
+}
+
+fn fmt_anchor(version: &str, link: String) -> String {
+    format!("[{version}]: {link}")
+}
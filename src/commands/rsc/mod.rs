@@ -0,0 +1,221 @@
+use colored::*;
+
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use std::time::{Duration, Instant};
+
+use crate::{config::CheckLevel, context::Context, error::Result, workspace};
+
+use self::{
+    checks::{checks, Candidate, Check},
+    report::{Case, Outcome, Report},
+};
+
+mod checks;
+mod report;
+
+#[derive(Clone, Args, Debug, Serialize, Deserialize)]
+pub struct ReleaseSanityCheck {
+    /// The release version to check, if not provided, the version of the release tag pointing at
+    /// HEAD which isn't pushed to the remote yet will be used. If the tag has the package name
+    /// (see the `tag-template` option), only the tagged package is checked. If the remote is
+    /// unreachable, the tags at HEAD are considered not pushed. If no git tag is found, the check will be skipped.
+    version: Option<String>,
+    /// The prefix of the git tags to use for the changelog link validation, overrides the global
+    /// `tag-prefix` and `tag-template` options.
+    #[arg(short, long)]
+    tag_prefix: Option<String>,
+    /// The git ref to use as the head for the changelog link validation, overrides the global
+    /// `head` option.
+    #[arg(long)]
+    head: Option<String>,
+    /// The number of days the release date is allowed to differ from the current date.
+    /// If not provided, the `rsc.date_tolerance` config option or `0` will be used.
+    #[arg(long)]
+    date_tolerance: Option<u32>,
+    /// Run all the checks and print the report, instead of stopping at the first failure.
+    #[arg(long)]
+    #[serde(default)]
+    collect: bool,
+    /// Write the machine-readable report of the checks, `junit=<path>` or `json=<path>`.
+    /// Can be provided multiple times.
+    #[arg(long, value_name = "FORMAT=PATH")]
+    #[serde(default)]
+    report: Vec<Report>,
+}
+
+impl ReleaseSanityCheck {
+    pub async fn run(&self, ctx: &Context) -> Result<()> {
+        let ctx = &ctx.with_refs(self.tag_prefix.clone(), self.head.clone());
+        let Some((ver, tagged)) = self.get_release(ctx)? else {
+            ctx.info("No version tag found, skipping release sanity check...");
+            return Ok(());
+        };
+
+        ctx.debug(format!("Release package version: {ver}").as_str());
+
+        // Only the tagged package is checked if the tag has the package name
+        let ctx = &match &tagged {
+            Some(package) => {
+                ctx.debug(format!("Release tag of the {package} package").as_str());
+                ctx.with_tag_package(package.clone())
+            }
+            None => ctx.clone(),
+        };
+
+        let checks = checks();
+        let levels = check_levels(ctx, &checks)?;
+        let release = Candidate::new(ctx, ver.clone(), self.date_tolerance);
+        let packages = package_candidates(ctx, &release, tagged.as_deref().or(ctx.package()))?;
+
+        let mut cases = vec![];
+        let mut failure = None;
+        'checks: for (check, level) in checks.iter().zip(levels) {
+            let candidates = match (check.per_package(), packages.is_empty()) {
+                (true, false) => packages.iter().collect::<Vec<_>>(),
+                _ => vec![&release],
+            };
+
+            for candidate in candidates {
+                let mut case = Case {
+                    check: check.name(),
+                    package: candidate.package.clone(),
+                    outcome: Outcome::Skipped,
+                    duration: Duration::ZERO,
+                };
+
+                if level == CheckLevel::Off {
+                    ctx.debug(format!("Skipping {} check", case.name()).as_str());
+                    cases.push(case);
+                    continue;
+                }
+
+                ctx.debug(format!("Running {} check", case.name()).as_str());
+
+                let started = Instant::now();
+                let result = check.run(&candidate.ctx, candidate);
+                case.duration = started.elapsed();
+
+                case.outcome = match result {
+                    Ok(()) => Outcome::Passed,
+                    Err(err) if level == CheckLevel::Warn => {
+                        ctx.warn(&format!("{} check failed: {}", case.name(), err.message()));
+                        Outcome::Warned(err.message().to_string())
+                    }
+                    Err(err) if !self.collect => {
+                        case.outcome = Outcome::Failed(err.message().to_string());
+                        cases.push(case);
+                        failure = Some(err);
+                        break 'checks;
+                    }
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Outcome::Failed(err.message().to_string())
+                    }
+                };
+                cases.push(case);
+            }
+        }
+
+        if self.collect {
+            print_report(ctx, &cases);
+        }
+
+        for report in &self.report {
+            report.write(ctx, &ver, &cases)?;
+        }
+
+        if let Some(err) = failure {
+            return Err(err);
+        }
+
+        let failed = cases
+            .iter()
+            .filter(|case| matches!(case.outcome, Outcome::Failed(_)))
+            .count();
+        if failed > 0 {
+            return Err(format!("{failed} of {} checks failed", cases.len()).into());
+        }
+
+        ctx.success("Release is sane");
+        Ok(())
+    }
+
+    /// Get the release version with the name of the tagged package, if the version is taken from
+    /// the release tag with the package name.
+    fn get_release(&self, ctx: &Context) -> Result<Option<(String, Option<String>)>> {
+        match &self.version {
+            Some(version) => Ok(Some((version.clone(), None))),
+            None => Ok(ctx.git_tag()?.clone().map(|tag| (tag.version, tag.package))),
+        }
+    }
+}
+
+/// Get the configured levels of the checks, or their default levels.
+fn check_levels(ctx: &Context, checks: &[Box<dyn Check>]) -> Result<Vec<CheckLevel>> {
+    let configured = &ctx.rsc_config().checks;
+
+    if let Some(unknown) = configured
+        .keys()
+        .find(|name| !checks.iter().any(|check| check.name() == name.as_str()))
+    {
+        let names = checks.iter().map(|check| check.name()).collect::<Vec<_>>();
+        return Err(format!(
+            "Unknown rsc check \"{unknown}\", available checks: {}",
+            names.join(", ")
+        )
+        .into());
+    }
+
+    Ok(checks
+        .iter()
+        .map(|check| {
+            configured
+                .get(check.name())
+                .copied()
+                .unwrap_or(check.default_level())
+        })
+        .collect())
+}
+
+/// Get the releases of the workspace packages (or only the selected one), empty if the workspace
+/// path is not set.
+fn package_candidates(
+    ctx: &Context,
+    release: &Candidate,
+    selected: Option<&str>,
+) -> Result<Vec<Candidate>> {
+    let Some(workspace_path) = ctx.workspace_path() else {
+        return Ok(vec![]);
+    };
+
+    let mut packages = workspace::packages(&workspace_path)?;
+    if let Some(selected) = selected {
+        packages.retain(|package| package.matches(selected));
+        if packages.is_empty() {
+            return Err(format!("Package {selected} not found in the workspace").into());
+        }
+    }
+
+    Ok(packages
+        .into_iter()
+        .map(|package| release.for_package(package))
+        .collect())
+}
+
+fn print_report(ctx: &Context, cases: &[Case]) {
+    let names = cases.iter().map(Case::name).collect::<Vec<_>>();
+    let name_width = names.iter().map(String::len).max().unwrap_or(0);
+
+    ctx.info("Report:");
+    for (name, case) in names.iter().zip(cases) {
+        let status = match &case.outcome {
+            Outcome::Passed => "ok".green().bold().to_string(),
+            Outcome::Warned(msg) => format!("{} {}", "warning:".yellow().bold(), msg.yellow()),
+            Outcome::Failed(msg) => format!("{} {}", "failed:".red().bold(), msg.red()),
+            Outcome::Skipped => "off".dimmed().to_string(),
+        };
+        println!("  {name:name_width$}  {status}");
+    }
+}
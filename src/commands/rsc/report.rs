@@ -0,0 +1,184 @@
+use std::{fs, str::FromStr, time::Duration};
+
+use eyre::eyre;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{commands::escape_xml, context::Context, error::Result};
+
+/// Machine-readable report of the checks, e.g. `junit=reports/rsc.xml`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Report {
+    format: ReportFormat,
+    path: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ReportFormat {
+    Junit,
+    Json,
+}
+
+impl FromStr for Report {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || format!("Invalid report \"{s}\", expected junit=<path> or json=<path>");
+        let (format, path) = s.split_once('=').ok_or_else(invalid)?;
+
+        let format = match format {
+            "junit" => ReportFormat::Junit,
+            "json" => ReportFormat::Json,
+            _ => return Err(invalid()),
+        };
+
+        if path.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            format,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// Outcome of a single check.
+pub(super) enum Outcome {
+    Passed,
+    Warned(String),
+    Failed(String),
+    Skipped,
+}
+
+/// Check run for the repository or a workspace package.
+pub(super) struct Case {
+    pub check: &'static str,
+    /// Name of the workspace package, `None` for the repository.
+    pub package: Option<String>,
+    pub outcome: Outcome,
+    pub duration: Duration,
+}
+
+impl Case {
+    /// Get the name of the case, e.g. `changelog_date (@foo/bar)`.
+    pub fn name(&self) -> String {
+        match &self.package {
+            Some(package) => format!("{} ({package})", self.check),
+            None => self.check.to_string(),
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        match self.outcome {
+            Outcome::Passed => "passed",
+            Outcome::Warned(_) => "warning",
+            Outcome::Failed(_) => "failed",
+            Outcome::Skipped => "skipped",
+        }
+    }
+
+    /// Get the failure or warning message without the terminal colors.
+    fn message(&self) -> Option<String> {
+        match &self.outcome {
+            Outcome::Warned(msg) | Outcome::Failed(msg) => Some(strip_colors(msg)),
+            Outcome::Passed | Outcome::Skipped => None,
+        }
+    }
+}
+
+impl Report {
+    /// Write the report of the cases to its path.
+    pub fn write(&self, ctx: &Context, version: &str, cases: &[Case]) -> Result<()> {
+        let contents = match self.format {
+            ReportFormat::Junit => junit(version, cases),
+            ReportFormat::Json => serde_json::to_string_pretty(&to_json(version, cases))
+                .map_err(|e| eyre!("Failed to serialize report: {e}"))?,
+        };
+
+        fs::write(&self.path, contents).map_err(|e| eyre!("Failed to write {}: {e}", self.path))?;
+        ctx.info(&format!("Report written to {}", self.path));
+        Ok(())
+    }
+}
+
+fn to_json(version: &str, cases: &[Case]) -> serde_json::Value {
+    let failed = cases
+        .iter()
+        .any(|case| matches!(case.outcome, Outcome::Failed(_)));
+
+    json!({
+        "version": version,
+        "status": if failed { "failed" } else { "passed" },
+        "time": total_time(cases),
+        "cases": cases
+            .iter()
+            .map(|case| {
+                json!({
+                    "check": case.check,
+                    "package": case.package,
+                    "status": case.status(),
+                    "time": case.duration.as_secs_f64(),
+                    "message": case.message(),
+                })
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn junit(version: &str, cases: &[Case]) -> String {
+    let count = |status: &str| cases.iter().filter(|case| case.status() == status).count();
+    let attributes = format!(
+        "tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\"",
+        cases.len(),
+        count("failed"),
+        count("skipped"),
+        total_time(cases)
+    );
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<testsuites name=\"rlx rsc\" {attributes}>\n"));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" {attributes}>\n",
+        escape_xml(&format!("rsc {version}"))
+    ));
+
+    for case in cases {
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            escape_xml(case.check),
+            escape_xml(case.package.as_deref().unwrap_or("rsc")),
+            case.duration.as_secs_f64()
+        ));
+
+        let message = case
+            .message()
+            .map(|msg| escape_xml(&msg))
+            .unwrap_or_default();
+        match case.outcome {
+            Outcome::Passed => xml.push_str("/>\n"),
+            Outcome::Skipped => xml.push_str(">\n      <skipped/>\n    </testcase>\n"),
+            Outcome::Failed(_) => xml.push_str(&format!(
+                ">\n      <failure message=\"{message}\">{message}</failure>\n    </testcase>\n"
+            )),
+            Outcome::Warned(_) => xml.push_str(&format!(
+                ">\n      <system-out>warning: {message}</system-out>\n    </testcase>\n"
+            )),
+        }
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn total_time(cases: &[Case]) -> f64 {
+    cases.iter().map(|case| case.duration.as_secs_f64()).sum()
+}
+
+fn strip_colors(text: &str) -> String {
+    Regex::new(r"\x1b\[[0-9;]*m")
+        .map(|regex| regex.replace_all(text, "").to_string())
+        .unwrap_or_else(|_| text.to_string())
+}
@@ -1,10 +1,7 @@
 use clap::Subcommand;
 use serde::{Deserialize, Serialize};
 
-use crate::{
-    context::Context,
-    error::{Error, Result},
-};
+use crate::{context::Context, error::Result};
 
 use super::*;
 
@@ -16,6 +13,9 @@ pub enum Commands {
     /// valid for the release)
     #[command(name = "rsc")]
     ReleaseSanityCheck(rsc::ReleaseSanityCheck),
+    /// Make a release: move [Unreleased] changes to the new version, apply the version to the
+    /// package.json file(s), commit the changes and create an annotated tag
+    Release(release::ReleaseCmd),
     /// Changelog commands, used to parse and manipulate changelog
     #[command(alias = "cl")]
     Changelog {
@@ -24,6 +24,10 @@ pub enum Commands {
         cmd: changelog::Changelog,
     },
 
+    /// Install the changelog merge driver: add the changelog to the `.gitattributes` file and
+    /// register the driver in the git config
+    InstallMergeDriver(install_merge_driver::InstallMergeDriverCmd),
+
     /// Version commands, used to manipulate versions
     #[command(alias = "v")]
     Version {
@@ -36,9 +40,11 @@ pub enum Commands {
 impl Commands {
     pub async fn run(self, context: &Context) -> Result<()> {
         match self {
-            Commands::ReleaseSanityCheck(cmd) => cmd.run(context).await.map_err(Error::from),
-            Commands::Version { cmd } => cmd.run(context).await.map_err(Error::from),
-            Commands::Changelog { cmd } => cmd.run(context).await.map_err(Error::from),
+            Commands::ReleaseSanityCheck(cmd) => cmd.run(context).await,
+            Commands::Release(cmd) => cmd.run(context).await,
+            Commands::Version { cmd } => cmd.run(context).await,
+            Commands::Changelog { cmd } => cmd.run(context).await,
+            Commands::InstallMergeDriver(cmd) => cmd.run(context).await,
         }
     }
 }
@@ -4,10 +4,10 @@ use std::{
     path::Path,
 };
 
-use crate::{context::Context, error::Result};
+use crate::{context::Context, error::Result, workspace::package_dirs};
 use clap::Args;
 use colored::Colorize;
-use eyre::{eyre, OptionExt};
+use eyre::eyre;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Args, Debug, Serialize, Deserialize)]
@@ -17,6 +17,10 @@ pub(crate) struct ApplyCmd {
 }
 
 impl ApplyCmd {
+    pub(crate) fn new(version: String) -> Self {
+        Self { version }
+    }
+
     pub(crate) fn run(self, ctx: &Context) -> Result<()> {
         let version = self.version.clone();
 
@@ -31,6 +35,20 @@ impl ApplyCmd {
         self.apply_version(ctx, version, None)
     }
 
+    /// Get paths of the `package.json` files the version will be applied to.
+    pub(crate) fn package_paths(ctx: &Context) -> Result<Vec<String>> {
+        let Some(workspace_path) = ctx.workspace_path() else {
+            return Ok(vec![format!("{}/package.json", ctx.root())]);
+        };
+
+        let mut paths = vec![];
+        for dir in package_dirs(&workspace_path)? {
+            paths.push(dir + "/package.json");
+        }
+
+        Ok(paths)
+    }
+
     fn validate_semver_compatibility(&self, version: String) -> Result<()> {
         semver::Version::parse(&version)
             .map_err(|e| format!("Version is not compatible with semantic versioning: {e}"))?;
@@ -39,7 +57,7 @@ impl ApplyCmd {
     }
 
     fn apply_version(&self, ctx: &Context, version: String, dir: Option<String>) -> Result<()> {
-        let path = dir.unwrap_or_else(|| ".".to_string()) + "/package.json";
+        let path = dir.unwrap_or_else(|| ctx.root().to_string()) + "/package.json";
 
         ctx.debug(format!("Reading package.json file from path {path}").as_str());
 
@@ -97,21 +115,8 @@ impl ApplyCmd {
         workspace_path: String,
         version: String,
     ) -> Result<()> {
-        let dir = Path::new(workspace_path.as_str());
-        for entry in dir
-            .read_dir()
-            .map_err(|e| eyre!("Failed to read workspace directory: {e}"))?
-        {
-            let entry = entry.map_err(|e| eyre!("Failed to read package directory: {e}"))?;
-            let path = entry.path();
-            if path.is_dir() {
-                let path_str = path
-                    .to_str()
-                    .ok_or_eyre("Failed to convert path to string")?
-                    .to_string();
-
-                self.apply_version(ctx, version.clone(), Some(path_str))?;
-            }
+        for dir in package_dirs(&workspace_path)? {
+            self.apply_version(ctx, version.clone(), Some(dir))?;
         }
 
         Ok(())
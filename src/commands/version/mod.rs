@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{context::Context, error::Result};
 
-use self::apply::ApplyCmd;
+pub(super) use self::apply::ApplyCmd;
 
 mod apply;
 
@@ -1,28 +1,244 @@
-use crate::error::Result;
-use eyre::Context;
+use crate::{category::BumpLevel, error::Result, forge::Forge};
 use serde::Deserialize;
-use std::path::Path;
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+};
 
-#[derive(Debug, Deserialize)]
+/// Names of the config files, their directory is the project root.
+const CONFIG_FILES: [&str; 4] = [".rlx.yml", ".rlx.toml", ".rlx.json", "rlx.config.json"];
+
+/// The `package.json` file is a config file if it has the config under the `rlx` key.
+const PACKAGE_JSON: &str = "package.json";
+const PACKAGE_JSON_KEY: &str = "rlx";
+
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct Config {
     #[serde(default)]
     pub workspace_path: Option<String>,
     pub debug: Option<bool>,
     pub changelog_path: Option<String>,
     pub remote_url: Option<String>,
+    pub remote: Option<String>,
     pub tag_prefix: Option<String>,
+    pub tag_template: Option<String>,
     pub head: Option<String>,
+    pub timezone: Option<String>,
+    pub forge: Option<Forge>,
+    #[serde(default)]
+    pub links: LinksConfig,
+    #[serde(default)]
+    pub categories: Vec<CategoryConfig>,
+    #[serde(default)]
+    pub autolink: AutolinkConfig,
+    #[serde(default)]
+    pub rsc: RscConfig,
+    #[serde(default)]
+    pub release: ReleaseConfig,
+    #[serde(default)]
+    pub convert: ConvertConfig,
+}
+
+/// Templates of the changelog links, set in the `links` section of the `.rlx.yml` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LinksConfig {
+    /// Template of the link of a release compared to the previous one.
+    pub compare: Option<String>,
+    /// Template of the link of the first release.
+    pub first_release: Option<String>,
+    /// Template of the link of the `[Unreleased]` section.
+    pub unreleased: Option<String>,
+}
+
+/// Changelog category, set in the `categories` list of the `.rlx.yml` file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CategoryConfig {
+    pub name: String,
+    /// Whether the entries are hidden from the `changelog get` output.
+    #[serde(default)]
+    pub hidden: bool,
+    /// Semver bump level required by the entries, used to infer the release version.
+    pub bump: Option<BumpLevel>,
+}
+
+/// Options of the issue and pull request references autolinking, set in the `autolink` section of
+/// the `.rlx.yml` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AutolinkConfig {
+    /// Whether to autolink the references in the `changelog format` and `changelog get` commands.
+    pub enabled: Option<bool>,
+    /// Reference patterns with their URL templates, take precedence over the forge ones.
+    #[serde(default)]
+    pub patterns: Vec<AutolinkPattern>,
+}
+
+/// Reference pattern, e.g. `PROJ-(\d+)`, with its URL template, e.g.
+/// `https://jira.example.com/browse/PROJ-$1`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutolinkPattern {
+    pub pattern: String,
+    pub url: String,
+}
+
+/// Options of the `rsc` command, set in the `rsc` section of the `.rlx.yml` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RscConfig {
+    /// Number of days the release date may differ from the current date.
+    pub date_tolerance: Option<u32>,
+    /// Levels of the checks by their names, e.g. `changelog_date: warn`.
+    #[serde(default)]
+    pub checks: HashMap<String, CheckLevel>,
+    /// Pattern of the branches the releases are made from, used by the `git_branch` check.
+    pub release_branch: Option<String>,
+    /// Options of the `semver_policy` check.
+    #[serde(default)]
+    pub semver: SemverConfig,
+}
+
+/// Options of the `semver_policy` check, set in the `rsc.semver` section of the `.rlx.yml` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SemverConfig {
+    /// Bump level required by the breaking changes before 1.0.0, `minor` by default.
+    pub pre_1_0_breaking_bump: Option<BreakingBump>,
+    /// Markers of the breaking change entries, e.g. `BREAKING`.
+    pub breaking_markers: Option<Vec<String>>,
+}
+
+/// Bump level of the breaking changes before 1.0.0, only `minor` and `major` are meaningful.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakingBump {
+    Minor,
+    Major,
+}
+
+/// Level of the `rsc` check, the failures of the `warn` checks are reported without failing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckLevel {
+    Error,
+    Warn,
+    Off,
+}
+
+/// Options of the `release` command, set in the `release` section of the `.rlx.yml` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReleaseConfig {
+    /// Template of the release commit message.
+    pub commit_message: Option<String>,
+    /// Template of the release tag message.
+    pub tag_message: Option<String>,
+}
+
+/// Options of the `changelog convert` command, set in the `convert` section of the `.rlx.yml` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConvertConfig {
+    /// Mapping of the source changelog section names to the Keep a Changelog categories.
+    #[serde(default)]
+    pub sections: HashMap<String, String>,
 }
 
 impl Config {
-    pub fn new() -> Result<Self> {
-        config::Config::builder()
-            .add_source(config::File::from(Path::new(".rlx.yml")).required(false))
+    /// Load the config from the provided file, or the config file in the project root, and the
+    /// environment variables.
+    pub fn new(root: &Path, path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => Some(path.to_path_buf()),
+            None => {
+                let files = config_files(root);
+                if files.len() > 1 {
+                    let names = files
+                        .iter()
+                        .map(|file| {
+                            file.strip_prefix(root)
+                                .unwrap_or(file)
+                                .display()
+                                .to_string()
+                        })
+                        .collect::<Vec<_>>();
+                    return Err(format!(
+                        "Multiple config files found in the project root: {}, keep only one of them or select it with the --config option",
+                        names.join(", ")
+                    )
+                    .into());
+                }
+                files.into_iter().next()
+            }
+        };
+
+        let mut builder = config::Config::builder();
+        if let Some(path) = path {
+            builder = if path.file_name().is_some_and(|name| name == PACKAGE_JSON) {
+                let config = package_json_config(&path)
+                    .ok_or_else(|| format!("No `{PACKAGE_JSON_KEY}` key in {}", path.display()))?;
+                builder.add_source(config::File::from_str(
+                    &config.to_string(),
+                    config::FileFormat::Json,
+                ))
+            } else {
+                builder.add_source(config::File::from(path))
+            };
+        }
+
+        builder
             .add_source(config::Environment::with_prefix("RLX"))
             .build()
-            .wrap_err_with(|| "Failed to build config")?
+            .map_err(|e| format!("Failed to build config: {e}"))?
             .try_deserialize()
-            .wrap_err_with(|| "Failed to deserialize config")
-            .map_err(Into::into)
+            .map_err(|e| format!("Failed to deserialize config: {e}").into())
+    }
+}
+
+/// Find the project root: the closest directory with a config file or the root of the git
+/// repository, so the search doesn't leave the repository, or the provided directory if there
+/// are neither.
+pub fn find_root(dir: &Path) -> PathBuf {
+    dir.ancestors()
+        .find(|dir| !config_files(dir).is_empty() || dir.join(".git").exists())
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+/// Get the config files in the directory, including the `package.json` file with the `rlx` key.
+fn config_files(dir: &Path) -> Vec<PathBuf> {
+    let package_json = dir.join(PACKAGE_JSON);
+
+    CONFIG_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.is_file())
+        .chain(package_json_config(&package_json).map(|_| package_json))
+        .collect()
+}
+
+/// Get the config under the `rlx` key of the `package.json` file.
+fn package_json_config(path: &Path) -> Option<serde_json::Value> {
+    File::open(path)
+        .ok()
+        .and_then(|file| serde_json::from_reader::<_, serde_json::Value>(file).ok())
+        .and_then(|mut json| json.get_mut(PACKAGE_JSON_KEY).map(serde_json::Value::take))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::find_root;
+
+    #[test]
+    fn find_root_stops_at_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let nested = repo.join("packages/foo");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir(repo.join(".git")).unwrap();
+        fs::write(dir.path().join(".rlx.yml"), "debug: true\n").unwrap();
+
+        assert_eq!(find_root(&nested), repo);
+
+        fs::write(nested.join(".rlx.yml"), "debug: true\n").unwrap();
+        assert_eq!(find_root(&nested), nested);
+        assert_eq!(find_root(dir.path()), dir.path());
     }
 }
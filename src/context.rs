@@ -1,30 +1,82 @@
-use std::process::Command;
+use std::{path::Path, sync::Arc};
 
-use eyre::{bail, Context as _Context, OptionExt, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use chrono_tz::Tz;
+use eyre::{bail, eyre, Context as _Context, OptionExt, Result};
 use once_cell::sync::OnceCell;
-use regex::Regex;
-
-use crate::{config::Config, log::Logger};
 
+use crate::{
+    category::Categories,
+    config::{self, AutolinkConfig, Config, ConvertConfig, ReleaseConfig, RscConfig},
+    forge::{Forge, LinkTemplates},
+    git::{GixRepository, Repository},
+    log::Logger,
+    remote,
+    tag::{ReleaseTag, TagTemplate},
+    workspace::{self, Package},
+};
+
+/// Changelog path to read the changelog from stdin and write it to stdout.
+pub const STDIO_PATH: &str = "-";
+
+#[derive(Clone)]
 pub struct Context {
     cfg: Config,
+    /// Project root directory, relative to the current directory.
+    root: String,
     log: Logger,
     head: String,
-    git_tag: OnceCell<Option<String>>,
+    repo: OnceCell<Arc<dyn Repository>>,
+    git_tag: OnceCell<Option<ReleaseTag>>,
     tag_prefix: Option<String>,
+    tag_template: Option<TagTemplate>,
+    /// Name of the package used in the tag template, resolved on the first use.
+    tag_package: OnceCell<Option<String>>,
     remote_url: OnceCell<String>,
+    remote: String,
     workspace_path: Option<String>,
     changelog_path: String,
+    timezone: Option<Tz>,
+    package: Option<String>,
+    forge: Option<Forge>,
+    categories: Categories,
 }
 
 impl Context {
     pub fn new_from_options(options: &super::Opts) -> Result<Self> {
-        let cfg = Config::new().wrap_err_with(|| "Failed to load config")?;
-
-        let workspace_path = options
-            .workspace_path
+        if let Some(dir) = &options.cwd {
+            std::env::set_current_dir(dir)
+                .wrap_err_with(|| format!("Failed to change the directory to {dir}"))?;
+        }
+
+        let cwd = std::env::current_dir().wrap_err("Failed to get the current directory")?;
+        let root = relative_root(&cwd, &config::find_root(&cwd));
+        let config_path = options
+            .config
             .clone()
-            .or_else(|| cfg.workspace_path.clone());
+            .or_else(|| std::env::var("RLX_CONFIG").ok());
+        let cfg = Config::new(Path::new(&root), config_path.as_deref().map(Path::new))
+            .wrap_err_with(|| "Failed to load config")?;
+
+        // The configured paths are relative to the directory of the config file, the project root
+        // if it's not selected
+        let config_dir = match config_path.as_deref().map(Path::new).and_then(Path::parent) {
+            Some(dir) if dir.as_os_str().is_empty() => ".".to_string(),
+            Some(dir) => dir.to_string_lossy().to_string(),
+            None => root.clone(),
+        };
+
+        Self::new(options, cfg, root, &config_dir)
+    }
+
+    /// Create the context from the options and the loaded config, the paths of the options are
+    /// relative to the current directory, the configured ones are relative to `config_dir`.
+    fn new(options: &super::Opts, cfg: Config, root: String, config_dir: &str) -> Result<Self> {
+        let workspace_path = options.workspace_path.clone().or_else(|| {
+            cfg.workspace_path
+                .clone()
+                .map(|path| resolve(config_dir, path))
+        });
 
         let debug = options.debug || cfg.debug.unwrap_or_default();
 
@@ -34,29 +86,83 @@ impl Context {
             .or_else(|| cfg.head.clone())
             .unwrap_or_else(|| "HEAD".to_owned());
 
-        let changelog_path = options
-            .changelog_path
-            .clone()
-            .or_else(|| cfg.changelog_path.clone())
-            .unwrap_or_else(|| "CHANGELOG.md".to_owned());
+        let changelog_path = options.changelog_path.clone().unwrap_or_else(|| {
+            let path = cfg
+                .changelog_path
+                .clone()
+                .unwrap_or_else(|| "CHANGELOG.md".to_owned());
+            resolve(config_dir, path)
+        });
 
         let tag_prefix = options
             .tag_prefix
             .clone()
             .or_else(|| cfg.tag_prefix.clone());
 
+        let tag_template = options
+            .tag_template
+            .as_ref()
+            .or(cfg.tag_template.as_ref())
+            .map(|template| TagTemplate::new(template))
+            .transpose()?;
+
+        let timezone = options
+            .timezone
+            .clone()
+            .or_else(|| cfg.timezone.clone())
+            .filter(|tz| !tz.eq_ignore_ascii_case("local"))
+            .map(|tz| {
+                tz.parse::<Tz>()
+                    .map_err(|e| eyre!("Invalid timezone \"{tz}\": {e}"))
+            })
+            .transpose()?;
+
+        let remote_url = OnceCell::new();
+        if let Some(url) = options.remote_url.as_ref().or(cfg.remote_url.as_ref()) {
+            let _ = remote_url.set(remote::normalize_url(url)?);
+        }
+
+        let remote = options
+            .remote
+            .clone()
+            .or_else(|| cfg.remote.clone())
+            .unwrap_or_else(|| "origin".to_owned());
+
+        let package = options.package.clone();
+
+        let forge = options.forge.or(cfg.forge);
+
+        let categories = Categories::new(&cfg.categories)?;
+
+        let log = Logger::new(debug, changelog_path == STDIO_PATH);
+
         Ok(Self {
             cfg,
+            root,
+            package,
+            forge,
+            categories,
             head,
             tag_prefix,
+            tag_template,
+            tag_package: OnceCell::new(),
             workspace_path,
             changelog_path,
-            log: Logger::new(debug),
+            timezone,
+            log,
+            repo: OnceCell::new(),
             git_tag: OnceCell::new(),
-            remote_url: OnceCell::new(),
+            remote_url,
+            remote,
         })
     }
 
+    /// Get the project root directory with the `.rlx.yml` file or the root of the git repository,
+    /// relative to the current directory (e.g. `../..`).
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
     pub fn workspace_path(&self) -> Option<String> {
         self.workspace_path.clone()
     }
@@ -69,65 +175,234 @@ impl Context {
         &self.changelog_path
     }
 
-    pub fn tag_prefix(&self) -> Option<String> {
-        self.tag_prefix.clone()
+    /// Get the context of a workspace package, which uses the package changelog and tags.
+    pub fn with_package(&self, package: &Package) -> Self {
+        Self {
+            changelog_path: package.changelog_path(self),
+            ..self.with_tag_package(package.name.clone())
+        }
     }
 
-    pub fn remote_url(&self) -> Result<&String> {
-        self.remote_url.get_or_try_init(|| {
-            if let Some(url) = &self.cfg.remote_url {
-                return normalize_origin_url(url.as_str());
-            }
+    /// Get the context which logs all the messages to stderr, so stdout only has the command
+    /// output, e.g. when running the command for several packages.
+    pub fn with_stderr_log(&self) -> Self {
+        Self {
+            log: self.log.with_stderr(),
+            ..self.clone()
+        }
+    }
 
-            let output = Command::new("git")
-                .arg("config")
-                .arg("--get")
-                .arg("remote.origin.url")
-                .output()?;
+    /// Get the context with the package name used in the tag template.
+    pub fn with_tag_package(&self, name: String) -> Self {
+        Self {
+            tag_package: OnceCell::with_value(Some(name)),
+            ..self.clone()
+        }
+    }
 
-            if !output.status.success() {
-                bail!("Git command executed with failing error code");
-            }
+    /// Get the name or directory name of the selected workspace package.
+    pub fn package(&self) -> Option<&str> {
+        self.package.as_deref()
+    }
 
-            let url = String::from_utf8_lossy(&output.stdout);
-            let url = url.trim();
+    /// Get the context with the tag prefix and head overridden by the command options, the tag
+    /// prefix takes precedence over the tag template.
+    pub fn with_refs(&self, tag_prefix: Option<String>, head: Option<String>) -> Self {
+        Self {
+            tag_template: match tag_prefix {
+                Some(_) => None,
+                None => self.tag_template.clone(),
+            },
+            tag_prefix: tag_prefix.or_else(|| self.tag_prefix.clone()),
+            head: head.unwrap_or_else(|| self.head.clone()),
+            ..self.clone()
+        }
+    }
 
-            if url.is_empty() {
-                bail!("Failed to get git remote URL");
-            }
+    /// Get the name of the package used in the tag template: the selected workspace package, or
+    /// the root package.
+    fn tag_package(&self) -> Result<Option<&str>> {
+        let name = self.tag_package.get_or_try_init(|| {
+            let (Some(selected), Some(path)) = (&self.package, &self.workspace_path) else {
+                return Ok(workspace::read_package_name(&self.root));
+            };
+
+            workspace::packages(path)?
+                .into_iter()
+                .find(|package| package.matches(selected))
+                .map(|package| Some(package.name))
+                .ok_or_else(|| eyre!("Package {selected} not found in the workspace"))
+        })?;
+
+        Ok(name.as_deref())
+    }
 
-            normalize_origin_url(url)
+    /// Get the prefix of the release tags, rendered by the tag template with the package name if
+    /// the template is set.
+    pub fn tag_prefix(&self) -> Result<Option<String>> {
+        match &self.tag_template {
+            Some(template) => template.prefix(self.tag_package()?).map(Some),
+            None => Ok(self.tag_prefix.clone()),
+        }
+    }
+
+    /// Get the tag template, or the template of the tags with the tag prefix if it's not set.
+    pub fn tag_template(&self) -> TagTemplate {
+        self.tag_template.clone().unwrap_or_else(|| {
+            TagTemplate::from_prefix(self.tag_prefix.as_deref().unwrap_or_default())
         })
     }
 
-    pub fn git_tag(&self) -> Result<&Option<String>> {
-        self.git_tag.get_or_try_init(|| {
-            let output = Command::new("git")
-                .arg("log")
-                .arg("-1")
-                .arg("--format=\"%D\"")
-                .output()?;
-
-            if !output.status.success() {
-                bail!("Git command executed with failing error code");
+    /// Get the git repository of the current directory, it's opened on the first use.
+    pub fn git(&self) -> Result<&dyn Repository> {
+        let repo = self.repo.get_or_try_init(|| {
+            GixRepository::discover(".").map(|repo| Arc::new(repo) as Arc<dyn Repository>)
+        })?;
+
+        Ok(repo.as_ref())
+    }
+
+    /// Get the context of the config without the options, the environment and the config files,
+    /// e.g. in tests.
+    #[cfg(test)]
+    pub fn from_config(cfg: Config) -> Self {
+        use clap::Parser;
+
+        let options = super::Opts::parse_from(["rlx", "changelog", "validate"]);
+        Self::new(&options, cfg, ".".to_string(), ".").expect("Invalid config")
+    }
+
+    /// Get the context with the provided git repository, e.g. a mock one.
+    #[cfg(test)]
+    pub fn with_repository(&self, repo: Arc<dyn Repository>) -> Self {
+        Self {
+            repo: OnceCell::with_value(repo),
+            git_tag: OnceCell::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Get the configured forge or detect it by the remote URL.
+    pub fn forge(&self) -> Result<Forge> {
+        match self.forge {
+            Some(forge) => Ok(forge),
+            None => Ok(Forge::detect(self.remote_url()?)),
+        }
+    }
+
+    /// Get the templates of the changelog links for the forge.
+    pub fn link_templates(&self) -> Result<LinkTemplates> {
+        Ok(LinkTemplates::new(self.forge()?, &self.cfg.links))
+    }
+
+    pub fn rsc_config(&self) -> &RscConfig {
+        &self.cfg.rsc
+    }
+
+    pub fn release_config(&self) -> &ReleaseConfig {
+        &self.cfg.release
+    }
+
+    pub fn convert_config(&self) -> &ConvertConfig {
+        &self.cfg.convert
+    }
+
+    /// Get the allowed changelog categories in their display order.
+    pub fn categories(&self) -> &Categories {
+        &self.categories
+    }
+
+    pub fn autolink_config(&self) -> &AutolinkConfig {
+        &self.cfg.autolink
+    }
+
+    /// Get the current date in the configured timezone.
+    ///
+    /// If the `SOURCE_DATE_EPOCH` environment variable is set, it's used as the current time to
+    /// make releases reproducible.
+    pub fn today(&self) -> Result<NaiveDate> {
+        let now = match std::env::var("SOURCE_DATE_EPOCH") {
+            Ok(epoch) => {
+                let secs = epoch
+                    .trim()
+                    .parse::<i64>()
+                    .wrap_err_with(|| format!("Invalid SOURCE_DATE_EPOCH value: \"{epoch}\""))?;
+                DateTime::from_timestamp(secs, 0).ok_or_eyre("SOURCE_DATE_EPOCH is out of range")?
             }
+            Err(_) => Utc::now(),
+        };
+
+        let today = match self.timezone {
+            Some(tz) => now.with_timezone(&tz).date_naive(),
+            None => now.with_timezone(&Local).date_naive(),
+        };
+
+        Ok(today)
+    }
 
-            let refs_report = String::from_utf8_lossy(&output.stdout);
-            let rx = Regex::new(r"(?i)tag: ([\w\d\-_.]+)")?;
-            let version_match = rx.captures(&refs_report);
+    /// Get the web URL of the repository, inferred from the URL of the git remote or the
+    /// `repository` field of the `package.json` file if the remote doesn't exist.
+    pub fn remote_url(&self) -> Result<&String> {
+        self.remote_url.get_or_try_init(|| {
+            let remote = self.remote.as_str();
+
+            let missing = match self.git().and_then(|git| git.remote_url(remote)) {
+                Ok(Some(url)) if !url.is_empty() => return remote::normalize_url(&url),
+                Ok(_) => format!("no \"{remote}\" remote"),
+                Err(e) => format!("git remote \"{remote}\" is unavailable ({e})"),
+            };
+            self.debug(&format!("Failed to get git remote URL: {missing}"));
+
+            remote::package_repository_url(&self.root)?.ok_or_else(|| {
+                eyre!("Failed to get git remote URL: {missing} and no `repository` field in package.json")
+            })
+        })
+    }
 
-            if version_match.is_none() {
+    /// Get the release tag pointing at the `HEAD` which isn't pushed to the remote yet, parsed by
+    /// the tag template. Only the tags matching the template are considered.
+    pub fn git_tag(&self) -> Result<&Option<ReleaseTag>> {
+        self.git_tag.get_or_try_init(|| {
+            let template = self.tag_template();
+            let tags = self
+                .git()?
+                .tags_at("HEAD")?
+                .iter()
+                .filter_map(|tag| template.parse(tag))
+                .collect::<Vec<_>>();
+
+            if tags.is_empty() {
                 return Ok(None);
             }
 
-            Ok(version_match
-                .ok_or_eyre("Failed to get version from git tag")?
-                .get(1)
-                .map(|m| m.as_str().to_string()))
+            // An unreachable remote (e.g. offline) doesn't block the check, all the tags are taken
+            let pushed = self.git()?.remote_tags(&self.remote).unwrap_or_else(|e| {
+                self.warn(&format!(
+                    "Failed to get the tags of the \"{}\" remote, considering the tags at HEAD as not pushed: {e}",
+                    self.remote
+                ));
+                vec![]
+            });
+            let mut unpushed = tags
+                .into_iter()
+                .filter(|tag| !pushed.contains(&tag.name))
+                .collect::<Vec<_>>();
+
+            if unpushed.len() > 1 {
+                let tags = unpushed
+                    .iter()
+                    .map(|tag| tag.name.as_str())
+                    .collect::<Vec<_>>();
+                bail!(
+                    "Several not pushed release tags point at HEAD: {}, provide the version explicitly",
+                    tags.join(", ")
+                );
+            }
+
+            Ok(unpushed.pop())
         })
     }
 
-    #[allow(dead_code)]
     pub fn error(&self, msg: &str) {
         self.log.error(msg);
     }
@@ -145,6 +420,10 @@ impl Context {
         self.log.success(msg);
     }
 
+    pub fn warn(&self, msg: &str) {
+        self.log.warn(msg);
+    }
+
     pub fn success_fmt(&self, msg: &str) {
         self.log.success_fmt(msg);
     }
@@ -154,7 +433,98 @@ impl Context {
     }
 }
 
-fn normalize_origin_url(url: &str) -> Result<String> {
-    let rx = Regex::new(r"git@(.+):(.+)\.git")?;
-    Ok(rx.replace(url, "https://$1/$2").to_string())
+/// Get the path of the root directory relative to the current directory it contains.
+fn relative_root(cwd: &Path, root: &Path) -> String {
+    let depth = cwd
+        .strip_prefix(root)
+        .map(|path| path.components().count())
+        .unwrap_or_default();
+
+    match depth {
+        0 => ".".to_string(),
+        _ => vec![".."; depth].join("/"),
+    }
+}
+
+/// Resolve the configured path relative to the directory of the config file.
+fn resolve(dir: &str, path: String) -> String {
+    if dir == "." || path == STDIO_PATH || Path::new(&path).is_absolute() {
+        return path;
+    }
+
+    format!("{dir}/{}", path.trim_start_matches("./"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use eyre::{eyre, Result};
+
+    use super::Context;
+    use crate::{config::Config, git::Repository};
+
+    /// Repository with the tags at `HEAD` and the tags of the remote, `None` if it's unreachable.
+    struct MockRepository {
+        tags_at: Vec<&'static str>,
+        remote_tags: Option<Vec<&'static str>>,
+    }
+
+    impl Repository for MockRepository {
+        fn tags_at(&self, _rev: &str) -> Result<Vec<String>> {
+            Ok(self.tags_at.iter().map(ToString::to_string).collect())
+        }
+
+        fn remote_tags(&self, _remote: &str) -> Result<Vec<String>> {
+            let tags = self
+                .remote_tags
+                .as_ref()
+                .ok_or_else(|| eyre!("unreachable"))?;
+            Ok(tags.iter().map(ToString::to_string).collect())
+        }
+    }
+
+    fn context(repo: MockRepository) -> Context {
+        let cfg = Config {
+            tag_prefix: Some("v".to_string()),
+            ..Config::default()
+        };
+        Context::from_config(cfg).with_repository(Arc::new(repo))
+    }
+
+    fn git_tag(repo: MockRepository) -> Result<Option<String>> {
+        let ctx = context(repo);
+        let tag = ctx.git_tag()?;
+        Ok(tag.as_ref().map(|tag| tag.version.clone()))
+    }
+
+    #[test]
+    fn git_tag_not_pushed() {
+        let repo = MockRepository {
+            tags_at: vec!["v1.0.0", "v1.1.0", "latest"],
+            remote_tags: Some(vec!["v1.0.0", "latest"]),
+        };
+        assert_eq!(git_tag(repo).unwrap().as_deref(), Some("1.1.0"));
+
+        let repo = MockRepository {
+            tags_at: vec!["v1.0.0"],
+            remote_tags: Some(vec!["v1.0.0"]),
+        };
+        assert_eq!(git_tag(repo).unwrap(), None);
+
+        let repo = MockRepository {
+            tags_at: vec!["v1.0.0", "v1.1.0"],
+            remote_tags: Some(vec![]),
+        };
+        assert!(git_tag(repo).is_err());
+    }
+
+    #[test]
+    fn git_tag_unreachable_remote() {
+        let repo = MockRepository {
+            tags_at: vec!["v1.1.0", "latest"],
+            remote_tags: None,
+        };
+        assert_eq!(git_tag(repo).unwrap().as_deref(), Some("1.1.0"));
+    }
 }
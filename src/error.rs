@@ -23,6 +23,10 @@ impl Error {
             format: false,
         }
     }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 impl std::error::Error for Error {}
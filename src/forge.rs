@@ -0,0 +1,131 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::config::LinksConfig;
+
+/// Git forge hosting the repository, determines the shape of the changelog links.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Forge {
+    /// GitHub and GitHub Enterprise
+    Github,
+    /// GitLab, including self-hosted instances
+    Gitlab,
+    /// Bitbucket Cloud
+    Bitbucket,
+    /// Gitea, Forgejo and Codeberg
+    Gitea,
+}
+
+impl Forge {
+    /// Detect the forge by the host of the repository URL, falls back to GitHub if the host is
+    /// unknown (e.g. self-hosted instance with a custom domain).
+    pub fn detect(url: &str) -> Self {
+        let host = url
+            .split_once("://")
+            .map_or(url, |(_, rest)| rest)
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if host.contains("gitlab") {
+            Self::Gitlab
+        } else if host.contains("bitbucket") {
+            Self::Bitbucket
+        } else if ["gitea", "forgejo", "codeberg"]
+            .iter()
+            .any(|name| host.contains(name))
+        {
+            Self::Gitea
+        } else {
+            Self::Github
+        }
+    }
+
+    /// Get the issue and pull request reference patterns of the forge with their URL templates,
+    /// the `{url}` placeholder is replaced with the repository URL and `$1` with the number.
+    pub fn references(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Github => &[
+                (r"#(\d+)", "{url}/issues/$1"),
+                (r"GH-(\d+)", "{url}/issues/$1"),
+            ],
+            Self::Gitlab => &[
+                (r"#(\d+)", "{url}/-/issues/$1"),
+                (r"!(\d+)", "{url}/-/merge_requests/$1"),
+            ],
+            Self::Bitbucket => &[(r"#(\d+)", "{url}/issues/$1")],
+            Self::Gitea => &[
+                (r"#(\d+)", "{url}/issues/$1"),
+                (r"!(\d+)", "{url}/pulls/$1"),
+            ],
+        }
+    }
+
+    fn templates(&self) -> LinkTemplates {
+        let (compare, first_release, unreleased) = match self {
+            Self::Github => (
+                "{url}/compare/{previous}...{current}",
+                "{url}/releases/tag/{current}",
+                "{url}/compare/{previous}...{head}",
+            ),
+            Self::Gitlab => (
+                "{url}/-/compare/{previous}...{current}",
+                "{url}/-/tags/{current}",
+                "{url}/-/compare/{previous}...{head}",
+            ),
+            Self::Bitbucket => (
+                "{url}/branches/compare/{current}%0D{previous}",
+                "{url}/src/{current}",
+                "{url}/branches/compare/{head}%0D{previous}",
+            ),
+            Self::Gitea => (
+                "{url}/compare/{previous}...{current}",
+                "{url}/src/tag/{current}",
+                "{url}/compare/{previous}...{head}",
+            ),
+        };
+
+        LinkTemplates {
+            compare: compare.to_string(),
+            first_release: first_release.to_string(),
+            unreleased: unreleased.to_string(),
+        }
+    }
+}
+
+/// Templates of the changelog links, the `{url}`, `{previous}`, `{current}` and `{head}`
+/// placeholders are replaced with the repository URL, the previous and current release tags and
+/// the head reference.
+#[derive(Clone, Debug)]
+pub struct LinkTemplates {
+    /// Link of a release compared to the previous one.
+    pub compare: String,
+    /// Link of the first release, which has nothing to compare with.
+    pub first_release: String,
+    /// Link of the `[Unreleased]` section compared to the latest release.
+    pub unreleased: String,
+}
+
+impl LinkTemplates {
+    /// Get the templates of the forge, overridden by the configured ones.
+    pub fn new(forge: Forge, cfg: &LinksConfig) -> Self {
+        let defaults = forge.templates();
+
+        Self {
+            compare: cfg.compare.clone().unwrap_or(defaults.compare),
+            first_release: cfg.first_release.clone().unwrap_or(defaults.first_release),
+            unreleased: cfg.unreleased.clone().unwrap_or(defaults.unreleased),
+        }
+    }
+
+    /// Render the template with the provided values.
+    pub fn render(template: &str, url: &str, previous: &str, current: &str, head: &str) -> String {
+        template
+            .replace("{url}", url.trim_end_matches('/'))
+            .replace("{previous}", previous)
+            .replace("{current}", current)
+            .replace("{head}", head)
+    }
+}
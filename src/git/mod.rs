@@ -0,0 +1,40 @@
+use std::{
+    path::Path,
+    process::{Command, Output},
+};
+
+use eyre::{bail, Context, Result};
+
+pub use self::repository::{GixRepository, Repository};
+
+mod repository;
+
+/// Run a git command in the directory and return its output, whatever the exit status is.
+fn command(dir: &Path, args: &[&str]) -> Result<Output> {
+    Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .wrap_err("Failed to execute git command")
+}
+
+/// Run a git command in the directory and return its trimmed standard output.
+fn run(dir: &Path, args: &[&str]) -> Result<String> {
+    Ok(output(dir, args)?.trim().to_string())
+}
+
+/// Run a git command in the directory and return its standard output as is, e.g. when the
+/// whitespace is significant.
+fn output(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = command(dir, args)?;
+
+    if !output.status.success() {
+        bail!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -0,0 +1,624 @@
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use eyre::{bail, eyre, Result};
+use gix::{
+    bstr::ByteSlice, objs::Kind, refs::TargetRef, remote::Direction, revision::walk::Sorting,
+    traverse::commit::simple::CommitTimeOrder, ObjectId, ThreadSafeRepository,
+};
+
+use crate::git;
+
+/// Git tag with the dates it can be dated by.
+#[derive(Clone, Debug)]
+pub struct Tag {
+    pub name: String,
+    /// Tagger date for the annotated tags, commit date for the lightweight ones.
+    pub tag_date: NaiveDate,
+    /// Date of the tagged commit.
+    pub commit_date: NaiveDate,
+}
+
+/// Access to the git repository: config, refs, tags, commit history, blob contents, working tree
+/// and remote tags. Implemented by [`GixRepository`], can be replaced with a mock in tests which
+/// implements only the operations it needs, the other ones fail.
+pub trait Repository: Send + Sync {
+    /// Get the URL of the remote, `None` if the remote doesn't exist or has no URL.
+    fn remote_url(&self, _remote: &str) -> Result<Option<String>> {
+        unsupported("remote_url")
+    }
+
+    /// Get the commit hash the `HEAD` points at.
+    fn head_commit(&self) -> Result<String> {
+        unsupported("head_commit")
+    }
+
+    /// Get the commit hash the revision points at, `None` if the revision doesn't exist.
+    fn rev_commit(&self, _rev: &str) -> Result<Option<String>> {
+        unsupported("rev_commit")
+    }
+
+    fn tag_exists(&self, _tag: &str) -> Result<bool> {
+        unsupported("tag_exists")
+    }
+
+    /// Check if the tag is annotated rather than lightweight.
+    fn tag_is_annotated(&self, _tag: &str) -> Result<bool> {
+        unsupported("tag_is_annotated")
+    }
+
+    /// Get the name of the current branch, `None` if the `HEAD` is detached.
+    fn current_branch(&self) -> Result<Option<String>> {
+        unsupported("current_branch")
+    }
+
+    /// Get the paths with uncommitted changes relative to the repository root, including the
+    /// untracked files.
+    fn dirty_paths(&self) -> Result<Vec<String>> {
+        unsupported("dirty_paths")
+    }
+
+    /// Get the number of the upstream commits missing in the `HEAD`, `None` if the current branch
+    /// has no upstream. The remote-tracking branch is used as is, without fetching.
+    fn commits_behind_upstream(&self) -> Result<Option<usize>> {
+        unsupported("commits_behind_upstream")
+    }
+
+    /// Get the tags pointing at commits.
+    fn tags(&self) -> Result<Vec<Tag>> {
+        unsupported("tags")
+    }
+
+    /// Get the tags pointing at the revision.
+    fn tags_at(&self, _rev: &str) -> Result<Vec<String>> {
+        unsupported("tags_at")
+    }
+
+    /// Get subjects of the non-merge commits reachable from `to` but not from `from`, newest first.
+    fn commit_subjects(&self, _from: Option<&str>, _to: &str) -> Result<Vec<String>> {
+        unsupported("commit_subjects")
+    }
+
+    /// Get the contents of the file at the revision, the path is relative to the directory the
+    /// repository is opened from, i.e. the project root.
+    fn show_file(&self, _rev: &str, _path: &str) -> Result<String> {
+        unsupported("show_file")
+    }
+
+    /// Get the tags of the remote repository, the remote is queried over the network.
+    fn remote_tags(&self, _remote: &str) -> Result<Vec<String>> {
+        unsupported("remote_tags")
+    }
+
+    /// Set the local git config value.
+    fn set_config(&self, _key: &str, _value: &str) -> Result<()> {
+        unsupported("set_config")
+    }
+
+    /// Stage and commit only the provided paths, leaving other staged changes untouched.
+    fn commit(&self, _message: &str, _paths: &[String]) -> Result<()> {
+        unsupported("commit")
+    }
+
+    /// Create an annotated tag on the `HEAD`.
+    fn create_tag(&self, _tag: &str, _message: &str) -> Result<()> {
+        unsupported("create_tag")
+    }
+
+    /// Move the `HEAD` back to the provided commit and unstage the provided paths, the working
+    /// tree is left untouched.
+    fn reset(&self, _commit: &str, _paths: &[String]) -> Result<()> {
+        unsupported("reset")
+    }
+
+    /// Merge the changes between the `base` and `other` files into the `current` file line by
+    /// line, leaving conflict markers in place. Returns `true` if the merge is clean.
+    fn merge_file(&self, _current: &str, _base: &str, _other: &str) -> Result<bool> {
+        unsupported("merge_file")
+    }
+}
+
+/// Fail the operation the repository doesn't implement.
+fn unsupported<T>(operation: &str) -> Result<T> {
+    Err(eyre!(
+        "Git operation `{operation}` isn't supported by the repository"
+    ))
+}
+
+/// Repository read in-process with `gix`, the working tree, network and write operations run the
+/// `git` command.
+pub struct GixRepository {
+    repo: ThreadSafeRepository,
+    /// Directory the repository is opened from, the `git` commands run in it.
+    dir: PathBuf,
+}
+
+impl GixRepository {
+    /// Open the repository containing the directory.
+    pub fn discover(dir: impl AsRef<Path>) -> Result<Self> {
+        let repo = ThreadSafeRepository::discover(dir.as_ref())
+            .map_err(|e| eyre!("Failed to open the git repository: {e}"))?;
+
+        Ok(Self {
+            repo,
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    fn commit_id(repo: &gix::Repository, rev: &str) -> Result<Option<ObjectId>> {
+        let Ok(id) = repo.rev_parse_single(rev) else {
+            return Ok(None);
+        };
+
+        let commit = id
+            .object()
+            .map_err(|e| eyre!("Failed to read {rev}: {e}"))?
+            .peel_to_kind(Kind::Commit)
+            .ok();
+
+        Ok(commit.map(|commit| commit.id))
+    }
+
+    fn existing_commit_id(repo: &gix::Repository, rev: &str) -> Result<ObjectId> {
+        Self::commit_id(repo, rev)?.ok_or_else(|| eyre!("Revision {rev} doesn't exist"))
+    }
+}
+
+impl Repository for GixRepository {
+    fn remote_url(&self, remote: &str) -> Result<Option<String>> {
+        let repo = self.repo.to_thread_local();
+        if !repo.remote_names().iter().any(|name| *name == remote) {
+            return Ok(None);
+        }
+
+        let remote = repo
+            .find_remote(remote)
+            .map_err(|e| eyre!("Failed to read git remote \"{remote}\": {e}"))?;
+
+        Ok(remote
+            .url(Direction::Fetch)
+            .map(|url| url.to_bstring().to_string()))
+    }
+
+    fn head_commit(&self) -> Result<String> {
+        let repo = self.repo.to_thread_local();
+        let id = repo
+            .head_id()
+            .map_err(|e| eyre!("Failed to get HEAD commit: {e}"))?;
+
+        Ok(id.to_string())
+    }
+
+    fn rev_commit(&self, rev: &str) -> Result<Option<String>> {
+        let repo = self.repo.to_thread_local();
+        Ok(Self::commit_id(&repo, rev)?.map(|id| id.to_string()))
+    }
+
+    fn tag_exists(&self, tag: &str) -> Result<bool> {
+        let repo = self.repo.to_thread_local();
+        let reference = repo
+            .try_find_reference(format!("refs/tags/{tag}").as_str())
+            .map_err(|e| eyre!("Failed to read tag {tag}: {e}"))?;
+
+        Ok(reference.is_some())
+    }
+
+    fn tag_is_annotated(&self, tag: &str) -> Result<bool> {
+        let repo = self.repo.to_thread_local();
+        let reference = repo
+            .try_find_reference(format!("refs/tags/{tag}").as_str())
+            .map_err(|e| eyre!("Failed to read tag {tag}: {e}"))?
+            .ok_or_else(|| eyre!("Tag {tag} doesn't exist"))?;
+
+        let TargetRef::Object(id) = reference.target() else {
+            return Ok(false);
+        };
+        let object = repo
+            .find_header(id)
+            .map_err(|e| eyre!("Failed to read tag {tag}: {e}"))?;
+
+        Ok(object.kind() == Kind::Tag)
+    }
+
+    fn current_branch(&self) -> Result<Option<String>> {
+        let repo = self.repo.to_thread_local();
+        let name = repo
+            .head_name()
+            .map_err(|e| eyre!("Failed to read HEAD: {e}"))?;
+
+        Ok(name.map(|name| name.shorten().to_string()))
+    }
+
+    fn dirty_paths(&self) -> Result<Vec<String>> {
+        // The `-z` output isn't trimmed nor quoted, the entry status takes the first 3 characters
+        let output = git::output(&self.dir, &["status", "--porcelain", "-z"])?;
+        let mut fields = output.split('\0').filter(|field| !field.is_empty());
+        let mut paths = vec![];
+
+        while let Some(field) = fields.next() {
+            let (Some(status), Some(path)) = (field.get(..2), field.get(3..)) else {
+                bail!("Unexpected git status entry: {field}");
+            };
+            paths.push(path.to_string());
+
+            // The renamed and copied entries are followed by their original path
+            if status.contains(['R', 'C']) {
+                fields.next();
+            }
+        }
+
+        Ok(paths)
+    }
+
+    fn commits_behind_upstream(&self) -> Result<Option<usize>> {
+        let repo = self.repo.to_thread_local();
+        let Some(branch) = repo
+            .head_name()
+            .map_err(|e| eyre!("Failed to read HEAD: {e}"))?
+        else {
+            return Ok(None);
+        };
+
+        let upstream = match repo.branch_remote_tracking_ref_name(branch.as_ref(), Direction::Fetch)
+        {
+            Some(upstream) => upstream.map_err(|e| eyre!("Failed to get upstream branch: {e}"))?,
+            None => return Ok(None),
+        };
+        let Some(upstream) = Self::commit_id(&repo, &upstream.to_string())? else {
+            return Ok(None);
+        };
+        let head = Self::existing_commit_id(&repo, "HEAD")?;
+
+        let count = repo
+            .rev_walk([upstream])
+            .with_hidden([head])
+            .all()
+            .map_err(|e| eyre!("Failed to walk the upstream commits: {e}"))?
+            .count();
+
+        Ok(Some(count))
+    }
+
+    fn tags(&self) -> Result<Vec<Tag>> {
+        let repo = self.repo.to_thread_local();
+        let references = repo
+            .references()
+            .map_err(|e| eyre!("Failed to read git refs: {e}"))?;
+        let mut tags = vec![];
+
+        for reference in references
+            .tags()
+            .map_err(|e| eyre!("Failed to read git tags: {e}"))?
+        {
+            let reference = reference.map_err(|e| eyre!("Failed to read git tag: {e}"))?;
+            let name = reference.name().shorten().to_string();
+            let TargetRef::Object(id) = reference.target() else {
+                continue;
+            };
+
+            let object = repo
+                .find_object(id)
+                .map_err(|e| eyre!("Failed to read tag {name}: {e}"))?;
+            let tagger_date = match object.kind {
+                Kind::Tag => object
+                    .to_tag_ref()
+                    .tagger()
+                    .ok()
+                    .flatten()
+                    .and_then(|tagger| tagger.time().ok())
+                    .map(|time| to_date(time.seconds, time.offset))
+                    .transpose()?,
+                _ => None,
+            };
+
+            // Tags of the trees and blobs have no dates
+            let Ok(commit) = object.peel_to_kind(Kind::Commit) else {
+                continue;
+            };
+            let time = commit
+                .into_commit()
+                .time()
+                .map_err(|e| eyre!("Failed to read the commit date of tag {name}: {e}"))?;
+            let commit_date = to_date(time.seconds, time.offset)?;
+
+            tags.push(Tag {
+                name,
+                tag_date: tagger_date.unwrap_or(commit_date),
+                commit_date,
+            });
+        }
+
+        Ok(tags)
+    }
+
+    fn tags_at(&self, rev: &str) -> Result<Vec<String>> {
+        let repo = self.repo.to_thread_local();
+        let target = Self::existing_commit_id(&repo, rev)?;
+        let references = repo
+            .references()
+            .map_err(|e| eyre!("Failed to read git refs: {e}"))?;
+        let mut tags = vec![];
+
+        for reference in references
+            .tags()
+            .map_err(|e| eyre!("Failed to read git tags: {e}"))?
+        {
+            let mut reference = reference.map_err(|e| eyre!("Failed to read git tag: {e}"))?;
+            let points_at = reference
+                .peel_to_kind(Kind::Commit)
+                .is_ok_and(|commit| commit.id == target);
+
+            if points_at {
+                tags.push(reference.name().shorten().to_string());
+            }
+        }
+
+        tags.sort();
+        Ok(tags)
+    }
+
+    fn commit_subjects(&self, from: Option<&str>, to: &str) -> Result<Vec<String>> {
+        let repo = self.repo.to_thread_local();
+        let tip = Self::existing_commit_id(&repo, to)?;
+        let hidden = from
+            .map(|from| Self::existing_commit_id(&repo, from))
+            .transpose()?;
+
+        let walk = repo
+            .rev_walk([tip])
+            .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+            .with_hidden(hidden)
+            .all()
+            .map_err(|e| eyre!("Failed to walk the commits of {to}: {e}"))?;
+
+        let mut subjects = vec![];
+        for info in walk {
+            let info = info.map_err(|e| eyre!("Failed to walk the commits of {to}: {e}"))?;
+            if info.parent_ids.len() > 1 {
+                continue;
+            }
+
+            let commit = info
+                .object()
+                .map_err(|e| eyre!("Failed to read commit {}: {e}", info.id))?;
+            let message = commit
+                .message()
+                .map_err(|e| eyre!("Failed to read commit {}: {e}", info.id))?;
+            let subject = message.summary().to_str_lossy().trim().to_string();
+
+            if !subject.is_empty() {
+                subjects.push(subject);
+            }
+        }
+
+        Ok(subjects)
+    }
+
+    fn show_file(&self, rev: &str, path: &str) -> Result<String> {
+        let repo = self.repo.to_thread_local();
+        let prefix = repo
+            .prefix()
+            .map_err(|e| eyre!("Failed to get the current directory in the repository: {e}"))?
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let repo_path = normalize(&prefix.join(path)).ok_or_else(|| {
+            eyre!("Failed to read {path} at {rev}: the path is outside the repository")
+        })?;
+
+        let tree = Self::commit_id(&repo, rev)?
+            .ok_or_else(|| eyre!("Failed to read {path} at {rev}: revision doesn't exist"))
+            .and_then(|id| {
+                repo.find_commit(id)
+                    .and_then(|commit| commit.tree())
+                    .map_err(|e| eyre!("Failed to read {path} at {rev}: {e}"))
+            })?;
+        let entry = tree
+            .lookup_entry_by_path(&repo_path)
+            .map_err(|e| eyre!("Failed to read {path} at {rev}: {e}"))?
+            .ok_or_else(|| eyre!("Failed to read {path} at {rev}: the file doesn't exist"))?;
+
+        let object = entry
+            .object()
+            .map_err(|e| eyre!("Failed to read {path} at {rev}: {e}"))?;
+        if object.kind != Kind::Blob {
+            bail!("Failed to read {path} at {rev}: not a file");
+        }
+
+        Ok(String::from_utf8_lossy(&object.data).to_string())
+    }
+
+    fn remote_tags(&self, remote: &str) -> Result<Vec<String>> {
+        Ok(
+            git::run(&self.dir, &["ls-remote", "--tags", "--refs", remote])?
+                .lines()
+                .filter_map(|line| line.split_once("refs/tags/"))
+                .map(|(_, tag)| tag.to_string())
+                .collect(),
+        )
+    }
+
+    fn set_config(&self, key: &str, value: &str) -> Result<()> {
+        git::run(&self.dir, &["config", key, value])?;
+        Ok(())
+    }
+
+    fn commit(&self, message: &str, paths: &[String]) -> Result<()> {
+        let mut args = vec!["add", "--"];
+        args.extend(paths.iter().map(String::as_str));
+        git::run(&self.dir, &args)?;
+
+        let mut args = vec!["commit", "--quiet", "--message", message, "--"];
+        args.extend(paths.iter().map(String::as_str));
+        git::run(&self.dir, &args)?;
+
+        Ok(())
+    }
+
+    fn create_tag(&self, tag: &str, message: &str) -> Result<()> {
+        git::run(&self.dir, &["tag", "--annotate", tag, "--message", message])?;
+        Ok(())
+    }
+
+    fn reset(&self, commit: &str, paths: &[String]) -> Result<()> {
+        git::run(&self.dir, &["reset", "--quiet", "--soft", commit])?;
+
+        let mut args = vec!["reset", "--quiet", commit, "--"];
+        args.extend(paths.iter().map(String::as_str));
+        git::run(&self.dir, &args)?;
+
+        Ok(())
+    }
+
+    fn merge_file(&self, current: &str, base: &str, other: &str) -> Result<bool> {
+        let args = [
+            "merge-file",
+            "-L",
+            "ours",
+            "-L",
+            "base",
+            "-L",
+            "theirs",
+            current,
+            base,
+            other,
+        ];
+        let output = git::command(&self.dir, &args)?;
+
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(code) if code > 0 => Ok(false),
+            _ => bail!(
+                "`git merge-file` failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        }
+    }
+}
+
+/// Resolve the `.` and `..` components of the relative path, `None` if it leaves the root.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut result = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => result.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !result.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    Some(result)
+}
+
+fn to_date(seconds: i64, offset: i32) -> Result<NaiveDate> {
+    let offset =
+        FixedOffset::east_opt(offset).ok_or_else(|| eyre!("Invalid git time offset: {offset}"))?;
+    let time = DateTime::from_timestamp(seconds, 0)
+        .ok_or_else(|| eyre!("Invalid git timestamp: {seconds}"))?;
+
+    Ok(time.with_timezone(&offset).date_naive())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, process::Command};
+
+    use super::{GixRepository, Repository};
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=rlx", "-c", "user.email=rlx@example.com"])
+            .args(["-c", "init.defaultBranch=main", "-c", "tag.gpgSign=false"])
+            .args(["-c", "commit.gpgSign=false"])
+            .args(args)
+            .output()
+            .expect("Failed to execute git command");
+
+        assert!(
+            output.status.success(),
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn commit(dir: &Path, file: &str, contents: &str) {
+        fs::write(dir.join(file), contents).unwrap();
+        git(dir, &["add", file]);
+        git(
+            dir,
+            &["commit", "--quiet", "--message", &format!("Update {file}")],
+        );
+    }
+
+    fn init(dir: &Path) -> GixRepository {
+        git(dir, &["init", "--quiet"]);
+        commit(dir, "file.txt", "one\n");
+        GixRepository::discover(dir).unwrap()
+    }
+
+    #[test]
+    fn dirty_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init(dir.path());
+        assert!(repo.dirty_paths().unwrap().is_empty());
+
+        fs::write(dir.path().join("file.txt"), "two\n").unwrap();
+        fs::write(dir.path().join("new file.txt"), "").unwrap();
+        assert_eq!(repo.dirty_paths().unwrap(), ["file.txt", "new file.txt"]);
+
+        git(dir.path(), &["mv", "file.txt", "moved.txt"]);
+        assert_eq!(repo.dirty_paths().unwrap(), ["moved.txt", "new file.txt"]);
+    }
+
+    #[test]
+    fn tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init(dir.path());
+        git(dir.path(), &["tag", "v1.0.0"]);
+        git(
+            dir.path(),
+            &["tag", "--annotate", "v1.1.0", "--message", "Release"],
+        );
+
+        assert!(repo.tag_exists("v1.0.0").unwrap());
+        assert!(!repo.tag_exists("v2.0.0").unwrap());
+        assert!(!repo.tag_is_annotated("v1.0.0").unwrap());
+        assert!(repo.tag_is_annotated("v1.1.0").unwrap());
+        assert_eq!(repo.tags_at("HEAD").unwrap(), ["v1.0.0", "v1.1.0"]);
+        assert_eq!(
+            repo.rev_commit("refs/tags/v1.1.0").unwrap(),
+            Some(repo.head_commit().unwrap())
+        );
+    }
+
+    #[test]
+    fn branch_and_upstream() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin = dir.path().join("origin");
+        let clone = dir.path().join("clone");
+        fs::create_dir(&origin).unwrap();
+
+        let repo = init(&origin);
+        assert_eq!(repo.current_branch().unwrap().as_deref(), Some("main"));
+        assert_eq!(repo.commits_behind_upstream().unwrap(), None);
+
+        git(dir.path(), &["clone", "--quiet", "origin", "clone"]);
+        let cloned = GixRepository::discover(&clone).unwrap();
+        assert_eq!(cloned.commits_behind_upstream().unwrap(), Some(0));
+
+        commit(&origin, "file.txt", "two\n");
+        git(&clone, &["fetch", "--quiet"]);
+        assert_eq!(cloned.commits_behind_upstream().unwrap(), Some(1));
+
+        git(&clone, &["checkout", "--quiet", "--detach"]);
+        assert_eq!(cloned.current_branch().unwrap(), None);
+        assert_eq!(cloned.commits_behind_upstream().unwrap(), None);
+    }
+}
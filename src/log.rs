@@ -3,11 +3,21 @@ use colored::*;
 #[derive(Debug, Clone)]
 pub struct Logger {
     debug: bool,
+    /// Print all the messages to stderr, used when stdout is taken by the changelog.
+    stderr: bool,
 }
 
 impl Logger {
-    pub fn new(debug: bool) -> Self {
-        Self { debug }
+    pub fn new(debug: bool, stderr: bool) -> Self {
+        Self { debug, stderr }
+    }
+
+    /// Get the logger which prints all the messages to stderr.
+    pub fn with_stderr(&self) -> Self {
+        Self {
+            stderr: true,
+            ..self.clone()
+        }
     }
 
     pub fn error(&self, msg: &str) {
@@ -20,22 +30,33 @@ impl Logger {
     }
 
     pub fn info(&self, msg: &str) {
-        println!("{}", MessageBuilder::new().info(msg).build());
+        self.print(MessageBuilder::new().info(msg).build());
     }
 
     pub fn success_fmt(&self, msg: &str) {
-        println!("{}", MessageBuilder::new().success_fmt(msg).build());
+        self.print(MessageBuilder::new().success_fmt(msg).build());
     }
 
     pub fn success(&self, msg: &str) {
-        println!("{}", MessageBuilder::new().success(msg).build());
+        self.print(MessageBuilder::new().success(msg).build());
+    }
+
+    pub fn warn(&self, msg: &str) {
+        eprintln!("{}", MessageBuilder::new().warn(msg).build());
     }
 
     pub fn debug(&self, msg: &str) {
         if !self.debug {
             return;
         }
-        println!("{}", MessageBuilder::new().debug(msg).build());
+        self.print(MessageBuilder::new().debug(msg).build());
+    }
+
+    fn print(&self, msg: String) {
+        match self.stderr {
+            true => eprintln!("{msg}"),
+            false => println!("{msg}"),
+        }
     }
 }
 
@@ -72,6 +93,11 @@ impl MessageBuilder {
         self.success_fmt(&format!("{}", msg.green()))
     }
 
+    pub fn warn(self, msg: &str) -> Self {
+        self.add(&format!("{}", "[Warning]: ".yellow().bold()))
+            .add(&format!("{}", msg.yellow()))
+    }
+
     pub fn debug(self, msg: &str) -> Self {
         self.add(&format!("{}", "[Debug]: ".blue().bold())).add(msg)
     }
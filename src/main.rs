@@ -1,9 +1,16 @@
+mod autolink;
+mod category;
 mod changelog_ext;
 mod commands;
 mod config;
 mod context;
 mod error;
+mod forge;
+mod git;
 mod log;
+mod remote;
+mod tag;
+mod workspace;
 
 use clap::{
     builder::styling::{AnsiColor as Ansi, Styles},
@@ -30,12 +37,32 @@ pub struct Opts {
     /// Can be set via `RLX_WORKSPACE_PATH` environment variable or `workspace_path` config option in the `.rlx.yml` file.
     #[arg(global = true, short, long)]
     pub workspace_path: Option<String>,
+    /// Run as if `rlx` was started in the provided directory instead of the current one.
+    ///
+    /// The project root is the closest directory with a config file or the root of the git repository. The
+    /// paths of the config file and environment variables are relative to the project root, or to the directory of the
+    /// config file selected with the `--config` option.
+    #[arg(global = true, short = 'C', long, value_name = "DIR")]
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Path to the config file, instead of the one found in the project root.
+    ///
+    /// The `.rlx.yml`, `.rlx.toml`, `.rlx.json` and `rlx.config.json` files, and the `rlx` key of the `package.json`
+    /// file are supported. Can be set via `RLX_CONFIG` environment variable.
+    #[arg(global = true, long, value_name = "PATH")]
+    #[serde(default)]
+    pub config: Option<String>,
+    /// The workspace package to run the changelog commands for, the name from the package.json or the
+    /// directory name. If not provided, the commands run for all the workspace packages.
+    #[arg(global = true, short, long)]
+    #[serde(default)]
+    pub package: Option<String>,
     /// Enable debug mode, which will print debug logs.
     ///
     /// Can be set via `RLX_DEBUG` environment variable or `debug` config option in the `.rlx.yml` file.
     #[arg(global = true, long)]
     pub debug: bool,
-    /// The path to the changelog file, defaults to `CHANGELOG.md`
+    /// The path to the changelog file, defaults to `CHANGELOG.md`. Use `-` for stdin and stdout
     ///
     /// Can be set via `RLX_CHANGELOG_PATH` environment variable or `changelog_path` config option in the `.rlx.yml` file.
     #[arg(global = true, alias = "cp", long)]
@@ -48,6 +75,12 @@ pub struct Opts {
     #[arg(global = true, alias = "url", long)]
     #[serde(default)]
     pub remote_url: Option<String>,
+    /// The name of the git remote to infer the remote URL from, defaults to `origin`.
+    ///
+    /// Can be set via `RLX_REMOTE` environment variable or `remote` config option in the `.rlx.yml` file.
+    #[arg(global = true, long)]
+    #[serde(default)]
+    pub remote: Option<String>,
     /// The tag prefix to use (e.g. `v`), used to generate compare links in the changelog.
     ///
     /// If not provided it will empty.
@@ -56,12 +89,36 @@ pub struct Opts {
     #[arg(global = true, short, long)]
     #[serde(default)]
     pub tag_prefix: Option<String>,
+    /// The template of the release tags in a mono-repo, e.g. `{name}@{version}` or
+    /// `{name}/v{version}`, where `{name}` is the package name. Must end with `{version}`, takes
+    /// precedence over the tag prefix.
+    ///
+    /// Can be set via `RLX_TAG_TEMPLATE` environment variable or `tag_template` config option in the `.rlx.yml` file.
+    #[arg(global = true, long)]
+    #[serde(default)]
+    pub tag_template: Option<String>,
     /// The head to use (by default `HEAD`), used to generate compare links in the changelog
     ///
     /// Can be set via `RLX_HEAD` environment variable or `head` config option in the `.rlx.yml` file.
     #[serde(default)]
     #[arg(global = true, long)]
     pub head: Option<String>,
+    /// The timezone used to determine the current date of a release (e.g. `UTC`, `Europe/Kyiv`),
+    /// defaults to the local timezone of the machine.
+    ///
+    /// If the `SOURCE_DATE_EPOCH` environment variable is set, it's used instead of the current time.
+    ///
+    /// Can be set via `RLX_TIMEZONE` environment variable or `timezone` config option in the `.rlx.yml` file.
+    #[serde(default)]
+    #[arg(global = true, long, alias = "tz")]
+    pub timezone: Option<String>,
+    /// The git forge hosting the repository, determines the shape of the compare links in the
+    /// changelog. If not provided it will be detected from the remote URL, falling back to GitHub.
+    ///
+    /// Can be set via `RLX_FORGE` environment variable or `forge` config option in the `.rlx.yml` file.
+    #[serde(default)]
+    #[arg(global = true, long, value_enum)]
+    pub forge: Option<forge::Forge>,
     #[command(subcommand)]
     cmd: commands::Commands,
 }
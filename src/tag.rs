@@ -0,0 +1,90 @@
+use eyre::{bail, eyre, Result};
+use regex::Regex;
+
+const NAME: &str = "{name}";
+const VERSION: &str = "{version}";
+
+/// Template of the release tags, e.g. `{name}@{version}` or `{name}/v{version}` in a mono-repo.
+/// The `{version}` placeholder is required at the end, `{name}` is replaced with the package name.
+#[derive(Clone, Debug)]
+pub struct TagTemplate {
+    template: String,
+    regex: Regex,
+}
+
+/// Release tag parsed by the tag template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReleaseTag {
+    pub name: String,
+    /// Name of the package, `None` if the template has no `{name}` placeholder.
+    pub package: Option<String>,
+    pub version: String,
+}
+
+impl TagTemplate {
+    pub fn new(template: &str) -> Result<Self> {
+        let prefix = template
+            .strip_suffix(VERSION)
+            .filter(|prefix| !prefix.contains(VERSION))
+            .ok_or_else(|| {
+                eyre!("Invalid tag template \"{template}\": it must end with the single {VERSION}")
+            })?;
+
+        if prefix.matches(NAME).count() > 1 {
+            bail!("Invalid tag template \"{template}\": {NAME} can be used only once");
+        }
+
+        let pattern = prefix
+            .split(NAME)
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(r"(?P<name>.+?)");
+        let regex = Regex::new(&format!(r"^{pattern}(?P<version>\d.*)$"))
+            .map_err(|e| eyre!("Invalid tag template \"{template}\": {e}"))?;
+
+        Ok(Self {
+            template: template.to_string(),
+            regex,
+        })
+    }
+
+    /// Get the template of the tags with the prefix, e.g. `v{version}`.
+    pub fn from_prefix(prefix: &str) -> Self {
+        Self {
+            template: format!("{prefix}{VERSION}"),
+            regex: Regex::new(&format!(r"^{}(?P<version>\d.*)$", regex::escape(prefix)))
+                .expect("escaped tag prefix is a valid pattern"),
+        }
+    }
+
+    /// Check if the tags have the package name.
+    pub fn has_name(&self) -> bool {
+        self.template.contains(NAME)
+    }
+
+    /// Get the prefix of the package tags, which precedes the version.
+    pub fn prefix(&self, package: Option<&str>) -> Result<String> {
+        let prefix = self.template.trim_end_matches(VERSION);
+
+        match (self.has_name(), package) {
+            (false, _) => Ok(prefix.to_string()),
+            (true, Some(package)) => Ok(prefix.replace(NAME, package)),
+            (true, None) => bail!(
+                "Tag template \"{}\" requires the package name, select the workspace package with the --package option",
+                self.template
+            ),
+        }
+    }
+
+    /// Parse the tag into the package name and the version, `None` if the tag doesn't match the
+    /// template.
+    pub fn parse(&self, tag: &str) -> Option<ReleaseTag> {
+        let captures = self.regex.captures(tag)?;
+
+        Some(ReleaseTag {
+            name: tag.to_string(),
+            package: captures.name("name").map(|name| name.as_str().to_string()),
+            version: captures.name("version")?.as_str().to_string(),
+        })
+    }
+}
@@ -0,0 +1,114 @@
+use std::{fs::File, path::Path};
+
+use eyre::{bail, eyre, OptionExt, Result};
+
+use crate::context::{Context, STDIO_PATH};
+
+/// Package of the mono-repo workspace.
+pub struct Package {
+    pub name: String,
+    pub dir: String,
+}
+
+impl Package {
+    /// Get the path of the package changelog, it has the same file name as the root changelog.
+    pub fn changelog_path(&self, ctx: &Context) -> String {
+        let file_name = Path::new(ctx.changelog_path())
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "CHANGELOG.md".to_string());
+
+        format!("{}/{file_name}", self.dir)
+    }
+
+    /// Check if the package has the provided name or directory name.
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || Path::new(&self.dir).file_name() == Some(name.as_ref())
+    }
+}
+
+/// Get the directories of the workspace packages.
+pub fn package_dirs(workspace_path: &str) -> Result<Vec<String>> {
+    let dir = Path::new(workspace_path);
+    let mut dirs = vec![];
+
+    for entry in dir
+        .read_dir()
+        .map_err(|e| eyre!("Failed to read workspace directory: {e}"))?
+    {
+        let entry = entry.map_err(|e| eyre!("Failed to read package directory: {e}"))?;
+        let path = entry.path();
+        if path.is_dir() {
+            let path_str = path
+                .to_str()
+                .ok_or_eyre("Failed to convert path to string")?
+                .to_string();
+
+            dirs.push(path_str);
+        }
+    }
+
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Get the workspace packages, the package name is taken from the `package.json` file if it
+/// exists, otherwise the directory name is used.
+pub fn packages(workspace_path: &str) -> Result<Vec<Package>> {
+    let mut packages = package_dirs(workspace_path)?
+        .into_iter()
+        .map(|dir| Package {
+            name: package_name(&dir),
+            dir,
+        })
+        .collect::<Vec<_>>();
+
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packages)
+}
+
+/// Get the workspace packages with changelogs, or only the selected package. Empty if the workspace
+/// path is not set or the packages have no changelogs, so the root changelog is used instead.
+pub fn changelog_packages(ctx: &Context) -> Result<Vec<Package>> {
+    let Some(workspace_path) = ctx.workspace_path() else {
+        return Ok(vec![]);
+    };
+
+    if ctx.changelog_path() == STDIO_PATH {
+        return Ok(vec![]);
+    }
+
+    let mut packages = packages(&workspace_path)?;
+
+    if let Some(selected) = ctx.package() {
+        packages.retain(|package| package.matches(selected));
+        if packages.is_empty() {
+            bail!("Package {selected} not found in the workspace");
+        }
+    }
+
+    packages.retain(|package| Path::new(&package.changelog_path(ctx)).exists());
+
+    if let (true, Some(selected)) = (packages.is_empty(), ctx.package()) {
+        bail!("Changelog of the {selected} package not found");
+    }
+
+    Ok(packages)
+}
+
+fn package_name(dir: &str) -> String {
+    read_package_name(dir).unwrap_or_else(|| {
+        Path::new(dir)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| dir.to_string())
+    })
+}
+
+/// Get the package name from the `package.json` file in the directory, if it exists.
+pub fn read_package_name(dir: &str) -> Option<String> {
+    File::open(Path::new(dir).join("package.json"))
+        .ok()
+        .and_then(|file| serde_json::from_reader::<_, serde_json::Value>(file).ok())
+        .and_then(|json| json.get("name")?.as_str().map(str::to_string))
+}